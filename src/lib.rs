@@ -0,0 +1,42 @@
+//! `ruled_labels` is a library helping with Github labels verifications based on a simple rule
+//! engine. The rules are defined using a yaml file. `ruled_labels` allows running a single check
+//! but also running a set of test cases to validate label set against your rules and ensuring
+//! your rules meet all your expectations.
+//!
+//! The `ruled-labels` cli is a thin layer on top of this library.
+//! You should check the [README](https://github.com/chevdor/ruled_labels/blob/master/README.md)
+//! of the project to gain a better understanding of what the functions are.
+//!
+//! If you are interested in write specs or test files, you can find some information below:
+//! - [Specs]
+//! - [Tests]
+//!
+//! ## example:
+//! ```no_run
+//! use ruled_labels::{LabelId, Specs};
+//! use std::{collections::HashSet, path::PathBuf};
+//!
+//! let specs = Specs::load(&PathBuf::from("specs.yaml")).unwrap();
+//! let labels: HashSet<LabelId> = specs.label_ids(&["B0", "X1"]).unwrap();
+//! let report = specs.run_checks(&labels, true, None, &None);
+//! println!("passed: {}", report.passed);
+//! ```
+
+pub mod rllib;
+
+pub use rllib::{
+	analyze::{Analysis, Finding},
+	check_report::{CheckReport, RuleOutcome, RuleReport},
+	coverage::{Coverage, RuleCoverage},
+	label_match::LabelMatch,
+	label_match_set::LabelMatchSet,
+	label_scheme::LabelScheme,
+	parsed_label::{LabelId, ParsedLabel},
+	rule::{Rule, RuleId, Tag},
+	rule_filter::RuleFilter,
+	rule_spec::RuleSpec,
+	severity::Severity,
+	specs::{Label, Specs},
+	suggest::{LabelChange, Suggestion},
+	tests::{TestSpec, Tests},
+};
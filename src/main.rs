@@ -1,32 +1,44 @@
 //! `ruled-labels` is a cli helping with Github labels verifications based on a simple rule engine.
-//! The rules are defined using a yaml file. `ruled-labels` allows running a single check but also
-//! running a set of test cases to validate label set against your rules and ensuring your rules
-//! meet all your expectations.
+//! All the logic lives in the [ruled_labels] library, this binary only parses the arguments
+//! and prints the results.
 //!
 //! You should check the [README](https://github.com/chevdor/ruled_labels/blob/master/README.md)
 //! of the project to gain a better understanding of what the functions are.
 //!
 //! For a deaper understand of the options you have to call `ruled-labels`, you may check out
 //! the [Opts](opts::Opts) and especially the list of available [SubCommand](opts::SubCommand)s.
-//!
-//! If you are interested in write specs or test files, you can find some information below:
-//! - [Specs](rllib::specs::Specs)
-//! - [Tests](rllib::tests::Tests)
 
 mod opts;
-mod rllib;
-
-use crate::rllib::{
-	parsed_label::LabelId,
-	rule::Rule,
-	specs::Specs,
-	test_result::{ResultPrinter, TestResult},
-	tests::Tests,
-};
+
 use clap::{crate_name, crate_version, Parser};
 use env_logger::Env;
 use opts::*;
-use std::{collections::HashSet, env, error::Error, path::PathBuf};
+use ruled_labels::{
+	rllib::{
+		analyze::{Analysis, AnalysisPrinter},
+		check_report::CheckReportPrinter,
+		common::set_to_string,
+		coverage::{Coverage, CoveragePrinter},
+		doc, gen_tests,
+		github::{self, GithubApi, GithubClient, ReportTarget},
+		lint::{LintReport, LintReportPrinter},
+		suggest::{Suggestion, SuggestionPrinter},
+		test_report::TestReportPrinter,
+		test_result::{ResultPrinter, TestResult},
+		webhook::{self, SpecsWatcher, WebhookHandler},
+	},
+	LabelId, Rule, Specs, Tests,
+};
+use serde::Serialize;
+use std::{
+	collections::HashSet, env, error::Error, fs, net::TcpListener, path::PathBuf, sync::Arc,
+};
+
+/// Print any of our reports as pretty json on stdout.
+fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn Error>> {
+	println!("{}", serde_json::to_string_pretty(value)?);
+	Ok(())
+}
 
 /// This is the entry point of the `ruled-labels` cli.
 fn main() -> Result<(), Box<dyn Error>> {
@@ -38,24 +50,35 @@ fn main() -> Result<(), Box<dyn Error>> {
 		SubCommand::List(cmd_opts) => {
 			log::debug!("list: {:#?}", cmd_opts);
 			let specs = Specs::load(&cmd_opts.spec_file)?;
-			println!("{specs}");
+			match opts.format {
+				OutputFormat::Text => println!("{specs}"),
+				OutputFormat::Json => print_json(&specs)?,
+			}
 			Ok(())
 		},
 
 		SubCommand::Lint(cmd_opts) => {
 			log::debug!("lint: {:#?}", cmd_opts);
-			let specs: Result<Specs, _> = Specs::load(&cmd_opts.spec_file);
-			let result = specs.is_ok();
-			ResultPrinter::new("Lint Result", TestResult::from(result))
-				.with_message_passed(&format!("The file {} looks OK", cmd_opts.spec_file.display()))
-				.with_message_failed(&format!(
-					"The file {} contains errors",
-					cmd_opts.spec_file.display()
-				))
-				.with_color(!opts.no_color)
-				.print();
+			let report = LintReport::from_file(&cmd_opts.spec_file);
+			match opts.format {
+				OutputFormat::Text => {
+					LintReportPrinter::new(&report).with_color(!opts.no_color).print();
+					ResultPrinter::new("Lint Result", TestResult::from(report.valid))
+						.with_message_passed(&format!(
+							"The file {} looks OK",
+							cmd_opts.spec_file.display()
+						))
+						.with_message_failed(&format!(
+							"The file {} contains errors",
+							cmd_opts.spec_file.display()
+						))
+						.with_color(!opts.no_color)
+						.print()
+				},
+				OutputFormat::Json => print_json(&report)?,
+			}
 
-			if result {
+			if report.valid {
 				std::process::exit(0)
 			} else {
 				std::process::exit(1)
@@ -66,21 +89,99 @@ fn main() -> Result<(), Box<dyn Error>> {
 			log::debug!("check: {:#?}", cmd_opts);
 			let specs: Specs = Specs::load(&cmd_opts.spec_file)?;
 
-			let label_ids: HashSet<LabelId> = if !cmd_opts.no_label {
-				cmd_opts.labels.iter().map(|s| s.id).collect()
-			} else {
-				HashSet::new()
+			let github = match (&cmd_opts.repo, cmd_opts.pr.or(cmd_opts.issue)) {
+				(Some(repo), Some(number)) => Some((
+					GithubClient::new(&cmd_opts.github_api, cmd_opts.token.clone())?,
+					repo,
+					number,
+				)),
+				_ => None,
 			};
 
-			let res =
-				specs.run_checks(&label_ids, true, !opts.no_color, opts.dev, cmd_opts.tags, &None);
-			let aggregated_result = res.iter().fold(true, |acc, x| match x {
-				Some(v) => acc && *v,
-				None => acc,
-			});
+			let names: Option<Vec<String>> = match &github {
+				Some((client, repo, number)) => Some(client.labels(repo, *number)?),
+				None => None,
+			};
+			let mut label_ids: HashSet<LabelId> = match &names {
+				Some(names) => {
+					let (label_ids, ignored) = specs.partition_label_ids(names);
+					if !ignored.is_empty() {
+						eprintln!(
+							"warning: ignoring the labels outside the label scheme: {}",
+							ignored.join(", ")
+						);
+					}
+					label_ids
+				},
+				None if cmd_opts.no_label => HashSet::new(),
+				None => specs.label_ids(&cmd_opts.labels)?,
+			};
+
+			let mut report = specs
+				.run_checks(&label_ids, true, cmd_opts.tags.clone(), &None)
+				.with_fail_on(cmd_opts.fail_on);
+			if (cmd_opts.suggest || cmd_opts.fix) && !report.passed {
+				report.suggestion = Some(Suggestion::new(&specs, &label_ids, None));
+			}
+
+			if let (true, Some(suggestion)) = (cmd_opts.fix, &report.suggestion) {
+				let Some(((client, repo, number), names)) = github.as_ref().zip(names.as_ref())
+				else {
+					return Err(
+						"--fix requires --pr or --issue to know which labels to change".into()
+					)
+				};
+				let mutations = github::plan_fix(suggestion, names, &specs)?;
+				if opts.format == OutputFormat::Text {
+					let prefix = if cmd_opts.dry_run { "Dry run" } else { "Fix" };
+					mutations.iter().for_each(|m| println!("{prefix}: {m} on {repo}#{number}"));
+				}
+
+				if !cmd_opts.dry_run {
+					github::apply_fix(client, repo, *number, &mutations)?;
+					label_ids =
+						suggestion.fixed_labels().expect("A fix was planned").into_iter().collect();
+					report = specs
+						.run_checks(&label_ids, true, cmd_opts.tags, &None)
+						.with_fail_on(cmd_opts.fail_on);
+				}
+			}
+			let aggregated_result = report.passed;
+			let exit_code = report.exit_code(cmd_opts.fail_on);
+
+			match (cmd_opts.report, &github) {
+				(None, _) => {},
+				(Some(_), None) =>
+					return Err("--report requires --pr or --issue to know where to report".into()),
+				(Some(ReportTarget::GithubStatus), _) if cmd_opts.pr.is_none() =>
+					return Err("--report github-status requires --pr".into()),
+				(Some(target), Some((client, repo, number))) => github::report(
+					client,
+					target,
+					repo,
+					*number,
+					&report,
+					&specs,
+					cmd_opts.fail_on,
+				)?,
+			}
+
+			if opts.format == OutputFormat::Json {
+				print_json(&report)?;
+				std::process::exit(exit_code)
+			}
+
+			CheckReportPrinter::new(&report)
+				.with_color(!opts.no_color)
+				.with_verbose(opts.dev)
+				.print();
+
+			if let Some(suggestion) = &report.suggestion {
+				SuggestionPrinter::new(suggestion).with_color(!opts.no_color).print();
+			}
 
 			if cmd_opts.faulty {
-				let faulty_rules: Vec<&Rule> = specs.find_faulty(res);
+				let faulty_rules: Vec<&Rule> = specs.find_faulty(&report);
 				if !faulty_rules.is_empty() {
 					println!("faulty_rules:");
 					faulty_rules.iter().for_each(|rule| println!("{rule:#?}"));
@@ -88,18 +189,42 @@ fn main() -> Result<(), Box<dyn Error>> {
 			}
 
 			if opts.dev {
-				let title = format!(
-					"{} v{} for labels {}",
-					specs.name,
-					specs.version,
-					label_ids.iter().map(|l| l.to_string()).collect::<Vec<String>>().join(", ")
-				);
+				let labels = set_to_string(&label_ids);
+				let title = format!("{} v{} for labels {labels}", specs.name, specs.version);
 				ResultPrinter::new(&title, TestResult::from(aggregated_result))
 					.with_color(!opts.no_color)
 					.print();
 			}
 
-			if aggregated_result {
+			std::process::exit(exit_code)
+		},
+
+		SubCommand::Analyze(cmd_opts) => {
+			log::debug!("analyze: {:#?}", cmd_opts);
+			let specs = Specs::load(&cmd_opts.spec_file)?;
+			let analysis = Analysis::new(&specs)?;
+			match opts.format {
+				OutputFormat::Text =>
+					AnalysisPrinter::new(&analysis).with_color(!opts.no_color).print(),
+				OutputFormat::Json => print_json(&analysis)?,
+			}
+
+			std::process::exit(analysis.severity().map_or(0, |severity| severity.exit_code()))
+		},
+
+		SubCommand::Suggest(cmd_opts) => {
+			log::debug!("suggest: {:#?}", cmd_opts);
+			let specs = Specs::load(&cmd_opts.spec_file)?;
+			let label_ids: HashSet<LabelId> = specs.label_ids(&cmd_opts.labels)?;
+
+			let suggestion = Suggestion::new(&specs, &label_ids, Some(cmd_opts.max_changes));
+			match opts.format {
+				OutputFormat::Text =>
+					SuggestionPrinter::new(&suggestion).with_color(!opts.no_color).print(),
+				OutputFormat::Json => print_json(&suggestion)?,
+			}
+
+			if suggestion.changes.is_some() {
 				std::process::exit(0)
 			} else {
 				std::process::exit(1)
@@ -121,24 +246,107 @@ fn main() -> Result<(), Box<dyn Error>> {
 			log::debug!("spec_file: {}", spec_file.display());
 			let specs = Specs::load(&spec_file)?;
 
-			println!("Tests specs: {}", &cmd_opts.test_specs.display());
-			println!("Specs file : {}", &spec_file.display());
+			let mut report = tests.run(&specs, cmd_opts.only, cmd_opts.all, &cmd_opts.filter);
+			if cmd_opts.coverage {
+				report.coverage = Some(Coverage::new(&specs, &report));
+			}
+
+			if let Some(junit) = &cmd_opts.junit {
+				log::debug!("Writing JUnit report to {}", junit.display());
+				fs::write(junit, report.to_junit_xml())?;
+			}
+
+			match opts.format {
+				OutputFormat::Text => {
+					println!("Tests specs: {}", &cmd_opts.test_specs.display());
+					println!("Specs file : {}", &spec_file.display());
+					TestReportPrinter::new(&report)
+						.with_color(!opts.no_color)
+						.with_dev(opts.dev)
+						.print();
+					if let Some(coverage) = &report.coverage {
+						CoveragePrinter::new(coverage).with_color(!opts.no_color).print();
+					}
+				},
+				OutputFormat::Json => print_json(&report)?,
+			}
+
+			let covered = match (&report.coverage, cmd_opts.min_coverage) {
+				(Some(coverage), Some(min)) => coverage.percent >= f64::from(min),
+				_ => true,
+			};
+			if report.passed && covered {
+				std::process::exit(0)
+			} else {
+				std::process::exit(1)
+			}
+		},
+
+		SubCommand::GenTests(cmd_opts) => {
+			log::debug!("gen-tests: {:#?}", cmd_opts);
+			let specs = Specs::load(&cmd_opts.spec_file)?;
+
+			// `spec_file` is relative to the folder of the test file
+			let spec_file = match &cmd_opts.output {
+				Some(output) => {
+					let folder = output.parent().filter(|p| !p.as_os_str().is_empty());
+					let folder = fs::canonicalize(folder.unwrap_or_else(|| ".".as_ref()))?;
+					let spec_file = fs::canonicalize(&cmd_opts.spec_file)?;
+					spec_file.strip_prefix(&folder).map(PathBuf::from).unwrap_or(spec_file)
+				},
+				None => cmd_opts.spec_file.clone(),
+			};
+			let tests = gen_tests::generate(&specs, &spec_file);
+			let without_id = specs.rules.iter().filter(|rule| !rule.disabled && rule.id.is_none());
+			without_id.for_each(|rule| {
+				eprintln!("The rule `{}` has no id, no tests were generated for it", rule.name)
+			});
 
-			// TODO: The following is unaccurate as it does not consider that some tests are
-			// included/excluded by `only` and `all` println!(
-			// 	"Running {:?} test cases on your {:?} rules",
-			// 	tests.specs.specs.len(),
-			// 	specs.rules.len()
-			// );
+			let content = match opts.format {
+				OutputFormat::Text => serde_yaml::to_string(&tests)?,
+				OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(&tests)?),
+			};
+			match &cmd_opts.output {
+				Some(output) => fs::write(output, content)?,
+				None => print!("{content}"),
+			}
+			Ok(())
+		},
 
-			tests.run(
+		SubCommand::Serve(cmd_opts) => {
+			log::debug!("serve: {:#?}", cmd_opts);
+			let specs = SpecsWatcher::new(&cmd_opts.spec_file)?;
+			let client = GithubClient::new(&cmd_opts.github_api, cmd_opts.token)?;
+			let handler = WebhookHandler::new(
+				client,
+				&cmd_opts.secret,
+				cmd_opts.report,
+				cmd_opts.fail_on,
 				specs,
-				cmd_opts.only,
-				cmd_opts.all,
-				!opts.no_color,
-				opts.dev,
-				&cmd_opts.filter,
 			);
+
+			let listener = TcpListener::bind(&cmd_opts.listen)?;
+			println!(
+				"Listening on {} with specs {}",
+				listener.local_addr()?,
+				cmd_opts.spec_file.display()
+			);
+			webhook::serve(listener, Arc::new(handler), cmd_opts.max_connections)?;
+			Ok(())
+		},
+
+		SubCommand::Doc(cmd_opts) => {
+			log::debug!("doc: {:#?}", cmd_opts);
+			let specs = Specs::load(&cmd_opts.spec_file)?;
+			let mut doc = doc::render_markdown(&specs, cmd_opts.template.as_deref())?;
+			if cmd_opts.html {
+				doc = doc::markdown_to_html(&specs.name, &doc);
+			}
+
+			match &cmd_opts.output {
+				Some(output) => fs::write(output, doc)?,
+				None => print!("{doc}"),
+			}
 			Ok(())
 		},
 	}
@@ -1,18 +1,32 @@
 //! This module defines all the claps (cli) options and flags.
 
-use crate::rllib::{parsed_label::ParsedLabel, rule::Tag};
-use clap::{crate_authors, crate_version, Parser, Subcommand};
+use clap::{
+	builder::{EnumValueParser, TypedValueParser},
+	crate_authors, crate_version, Parser, Subcommand, ValueEnum,
+};
 use regex::Regex;
+use ruled_labels::{
+	rllib::{
+		github::{Repo, ReportTarget, DEFAULT_API_URL},
+		suggest::DEFAULT_MAX_CHANGES,
+		webhook::DEFAULT_MAX_CONNECTIONS,
+	},
+	Severity, Tag,
+};
 use std::path::PathBuf;
 
 /// This utility allows checking labels based on rules
 #[derive(Parser)]
 #[clap(version = crate_version!(), author = crate_authors!())]
 pub struct Opts {
-	// pub json: bool,
 	#[clap(subcommand)]
 	pub subcmd: SubCommand,
 
+	/// The output format. The `json` format is meant for machines and
+	/// is documented in the `JSON output` section of the documentation.
+	#[clap(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+	pub format: OutputFormat,
+
 	/// Output without any coloring, this is useful
 	/// for documentation and CI system where the color code
 	/// pollute the output.
@@ -24,6 +38,66 @@ pub struct Opts {
 	pub dev: bool,
 }
 
+/// The supported output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+	/// Human readable output
+	Text,
+
+	/// Machine readable output
+	Json,
+}
+
+/// The command line values of [Severity]
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SeverityArg {
+	/// The failure is only informative
+	Info,
+
+	/// The failure should be looked at but is not blocking
+	Warning,
+
+	/// The failure is blocking, this is the default
+	Error,
+}
+
+impl From<SeverityArg> for Severity {
+	fn from(severity: SeverityArg) -> Self {
+		match severity {
+			SeverityArg::Info => Severity::Info,
+			SeverityArg::Warning => Severity::Warning,
+			SeverityArg::Error => Severity::Error,
+		}
+	}
+}
+
+fn severity_parser() -> impl TypedValueParser<Value = Severity> {
+	EnumValueParser::<SeverityArg>::new().map(Severity::from)
+}
+
+/// The command line values of [ReportTarget]
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ReportTargetArg {
+	/// A single comment, created on failure and updated on the next runs
+	GithubComment,
+
+	/// A commit status on the head of the pull request
+	GithubStatus,
+}
+
+impl From<ReportTargetArg> for ReportTarget {
+	fn from(target: ReportTargetArg) -> Self {
+		match target {
+			ReportTargetArg::GithubComment => ReportTarget::GithubComment,
+			ReportTargetArg::GithubStatus => ReportTarget::GithubStatus,
+		}
+	}
+}
+
+fn report_target_parser() -> impl TypedValueParser<Value = ReportTarget> {
+	EnumValueParser::<ReportTargetArg>::new().map(ReportTarget::from)
+}
+
 /// You can find all available commands below.
 #[derive(Debug, Subcommand)]
 pub enum SubCommand {
@@ -36,8 +110,23 @@ pub enum SubCommand {
 	#[clap(version = crate_version!(), author = crate_authors!())]
 	Check(CheckOpts),
 
+	#[clap(version = crate_version!(), author = crate_authors!())]
+	Analyze(AnalyzeOpts),
+
+	#[clap(version = crate_version!(), author = crate_authors!())]
+	Suggest(SuggestOpts),
+
 	#[clap(version = crate_version!(), author = crate_authors!())]
 	Test(TestOpts),
+
+	#[clap(version = crate_version!(), author = crate_authors!())]
+	GenTests(GenTestsOpts),
+
+	#[clap(version = crate_version!(), author = crate_authors!())]
+	Serve(ServeOpts),
+
+	#[clap(version = crate_version!(), author = crate_authors!())]
+	Doc(DocOpts),
 }
 
 /// List all the rules
@@ -56,6 +145,14 @@ pub struct LintOpts {
 	pub spec_file: PathBuf,
 }
 
+/// Find the rules that never pass, never apply, conflict or are redundant
+#[derive(Debug, Parser)]
+pub struct AnalyzeOpts {
+	/// Spec file
+	#[clap(index = 1, default_value = "specs.yaml", value_hint=clap::ValueHint::FilePath)]
+	pub spec_file: PathBuf,
+}
+
 /// Check label set against the rules
 #[derive(Debug, Parser)]
 pub struct CheckOpts {
@@ -66,23 +163,87 @@ pub struct CheckOpts {
 	/// The list of labels. You may pass then as `-l A1,B1` or `-l A1 -l B1`.
 	///
 	/// NOTE: The following calls are NOT valid: `-l A1, B1` or `-l A1 B1`
-	#[clap(long, short, required = true, num_args=1.., value_delimiter = ',')]
-	pub labels: Vec<ParsedLabel>,
+	#[clap(long, short, required_unless_present_any = ["no_label", "pr", "issue"], num_args=1.., value_delimiter = ',')]
+	pub labels: Vec<String>,
 
 	/// Depending on your rules, if may be ok to have no labels.
 	#[clap(long, short, conflicts_with = "labels")]
 	pub no_label: bool,
 
+	/// The Github repository, as `owner/name`, of the `--pr` or `--issue`
+	#[clap(long)]
+	pub repo: Option<Repo>,
+
+	/// Fetch the labels from this pull request instead of passing them with `--labels`
+	#[clap(long, requires = "repo", conflicts_with_all = ["labels", "no_label", "issue"])]
+	pub pr: Option<u64>,
+
+	/// Fetch the labels from this issue instead of passing them with `--labels`
+	#[clap(long, requires = "repo", conflicts_with_all = ["labels", "no_label"])]
+	pub issue: Option<u64>,
+
+	/// The Github token used to access the API
+	#[clap(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+	pub token: Option<String>,
+
+	/// The Github API url, you usually do not need to change it
+	#[clap(long, env = "GITHUB_API_URL", default_value = DEFAULT_API_URL)]
+	pub github_api: String,
+
+	/// Post the result of the check back to Github. This requires `--pr` or `--issue`
+	/// and a token allowed to write to the repository.
+	#[clap(long, value_parser = report_target_parser(), requires = "repo")]
+	pub report: Option<ReportTarget>,
+
 	/// Show details about the rules of the faulty tests
 	#[clap(long)]
 	pub faulty: bool,
 
+	/// The lowest severity of the failed rules making the check fail. The exit code is
+	/// 1 if an `error` rule failed, 2 for a `warning` and 3 for an `info` rule.
+	#[clap(long, value_parser = severity_parser(), default_value = "error")]
+	pub fail_on: Severity,
+
+	/// When the check fails, show the smallest set of labels to add or remove
+	/// to satisfy all the rules
+	#[clap(long)]
+	pub suggest: bool,
+
+	/// When the check fails, add and remove the labels of the `--pr` or `--issue` so that
+	/// all the rules pass. Only the labels defined in the specs are ever removed.
+	#[clap(long, requires = "repo")]
+	pub fix: bool,
+
+	/// With `--fix`, only show the changes that would be made
+	#[clap(long, requires = "fix")]
+	pub dry_run: bool,
+
 	/// If you pass optional tags here, only the checks containing
 	/// **all** those tags will run
 	#[clap(short, long, num_args=0..)]
 	pub tags: Option<Vec<Tag>>,
 }
 
+/// Show the smallest set of labels to add or remove to satisfy all the rules
+#[derive(Debug, Parser)]
+pub struct SuggestOpts {
+	/// Spec file
+	#[clap(index = 1, default_value = "specs.yaml", value_hint=clap::ValueHint::FilePath)]
+	pub spec_file: PathBuf,
+
+	/// The list of labels. You may pass then as `-l A1,B1` or `-l A1 -l B1`.
+	#[clap(long, short, required_unless_present = "no_label", num_args=1.., value_delimiter = ',')]
+	pub labels: Vec<String>,
+
+	/// Start from an empty set of labels
+	#[clap(long, short, conflicts_with = "labels")]
+	pub no_label: bool,
+
+	/// Give up after this number of changes. Trying more changes takes much longer.
+	#[clap(long, default_value_t = DEFAULT_MAX_CHANGES)]
+	pub max_changes: usize,
+}
+
 /// Run tests using rules and a test set
 #[derive(Debug, Parser)]
 pub struct TestOpts {
@@ -106,4 +267,84 @@ pub struct TestOpts {
 	/// You can pass any valid regexp.
 	#[clap(short, long)]
 	pub filter: Option<Regex>,
+
+	/// Write a JUnit XML report of the test results to this file
+	#[clap(long, value_hint=clap::ValueHint::FilePath)]
+	pub junit: Option<PathBuf>,
+
+	/// Report which rules the tests exercised
+	#[clap(long)]
+	pub coverage: bool,
+
+	/// Fail if less than this percentage of the enabled rules were tested
+	#[clap(long, requires = "coverage", value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub min_coverage: Option<u8>,
+}
+
+/// Generate a test file with passing, failing and boundary cases for each rule
+#[derive(Debug, Parser)]
+pub struct GenTestsOpts {
+	/// Spec file
+	#[clap(index = 1, default_value = "specs.yaml", value_hint=clap::ValueHint::FilePath)]
+	pub spec_file: PathBuf,
+
+	/// Write the tests to this file instead of stdout
+	#[clap(long, short, value_hint=clap::ValueHint::FilePath)]
+	pub output: Option<PathBuf>,
+}
+
+/// Run a webhook server checking the labels of the pull requests
+#[derive(Debug, Parser)]
+pub struct ServeOpts {
+	/// Spec file. It is reloaded when it changes on disk.
+	#[clap(index = 1, default_value = "specs.yaml", value_hint=clap::ValueHint::FilePath)]
+	pub spec_file: PathBuf,
+
+	/// The address to listen on
+	#[clap(long, default_value = "127.0.0.1:3000")]
+	pub listen: String,
+
+	/// The secret of the Github webhook, used to verify the signature of the payloads
+	#[clap(long, env = "WEBHOOK_SECRET", hide_env_values = true)]
+	pub secret: String,
+
+	/// The Github token used to access the API
+	#[clap(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+	pub token: Option<String>,
+
+	/// The Github API url, you usually do not need to change it
+	#[clap(long, env = "GITHUB_API_URL", default_value = DEFAULT_API_URL)]
+	pub github_api: String,
+
+	/// How the results are posted to Github
+	#[clap(long, value_parser = report_target_parser(), default_value = "github-status")]
+	pub report: ReportTarget,
+
+	/// The lowest severity of the failed rules making the check fail
+	#[clap(long, value_parser = severity_parser(), default_value = "error")]
+	pub fail_on: Severity,
+
+	/// The number of deliveries handled at once, the others are rejected with a 503
+	#[clap(long, default_value_t = DEFAULT_MAX_CONNECTIONS)]
+	pub max_connections: usize,
+}
+
+/// Generate the documentation of the labels and rules
+#[derive(Debug, Parser)]
+pub struct DocOpts {
+	/// Spec file
+	#[clap(index = 1, default_value = "specs.yaml", value_hint=clap::ValueHint::FilePath)]
+	pub spec_file: PathBuf,
+
+	/// A Tera template to use instead of the bundled one
+	#[clap(long, short, value_hint=clap::ValueHint::FilePath)]
+	pub template: Option<PathBuf>,
+
+	/// Convert the generated Markdown to HTML
+	#[clap(long)]
+	pub html: bool,
+
+	/// Write the documentation to this file instead of stdout
+	#[clap(long, short, value_hint=clap::ValueHint::FilePath)]
+	pub output: Option<PathBuf>,
 }
@@ -0,0 +1,447 @@
+//! Definitions of [Analysis], [Finding] and [AnalysisPrinter].
+//!
+//! The analysis checks the enabled rules of the [Specs] against every set of labels that can be
+//! made from the labels of the specs. This finds the rules that can never pass, the rules that
+//! never apply, the pairs of rules that never pass together and the redundant rules.
+//!
+//! The rules only count the labels matching their [LabelMatchSet]s, so the labels all the sets
+//! match alike are interchangeable: only the number of labels taken from each of these classes
+//! matters, which keeps the number of sets to try low.
+
+use super::{
+	label_match_set::LabelMatchSet, parsed_label::LabelId, severity::Severity, specs::Specs,
+};
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::{
+	collections::{BTreeMap, HashSet},
+	fmt::Display,
+};
+use termion::color;
+
+/// Beyond this number of sets of labels, the analysis takes too long.
+pub const MAX_SETS: usize = 1 << 16;
+
+/// Something the analysis found out about the rules
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Finding {
+	/// The rule applies to some sets of labels but never passes
+	Unsatisfiable { rule: String },
+
+	/// The `when` of the rule is never met, the rule is always skipped
+	Vacuous { rule: String },
+
+	/// The two rules apply together to some sets of labels but never pass together
+	Conflict { rules: [String; 2] },
+
+	/// The rule passes for all the sets of labels satisfying the other rules.
+	/// `implied_by` is a single rule implying it, if any.
+	Redundant { rule: String, implied_by: Option<String> },
+
+	/// No set of labels satisfies all the rules
+	NoValidSet,
+}
+
+impl Finding {
+	/// Conflicting or unsatisfiable rules are errors, useless rules are warnings.
+	pub fn severity(&self) -> Severity {
+		match self {
+			Finding::Vacuous { .. } | Finding::Redundant { .. } => Severity::Warning,
+			_ => Severity::Error,
+		}
+	}
+}
+
+impl Display for Finding {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Finding::Unsatisfiable { rule } =>
+				f.write_fmt(format_args!("The rule `{rule}` never passes when it applies")),
+			Finding::Vacuous { rule } => f.write_fmt(format_args!(
+				"The `when` of the rule `{rule}` is never met, the rule is always skipped"
+			)),
+			Finding::Conflict { rules: [a, b] } =>
+				f.write_fmt(format_args!("The rules `{a}` and `{b}` never pass together")),
+			Finding::Redundant { rule, implied_by: Some(other) } =>
+				f.write_fmt(format_args!("The rule `{rule}` is implied by the rule `{other}`")),
+			Finding::Redundant { rule, implied_by: None } =>
+				f.write_fmt(format_args!("The rule `{rule}` is implied by the other rules")),
+			Finding::NoValidSet => f.write_str("No set of labels satisfies all the rules"),
+		}
+	}
+}
+
+/// The result of [Analysis::new].
+#[derive(Debug, Serialize, Clone)]
+pub struct Analysis {
+	/// The labels the sets are made of, sorted. The labels no rule mentions are left out.
+	pub labels: Vec<LabelId>,
+
+	/// The labels grouped by the way the rules match them
+	pub classes: Vec<Vec<LabelId>>,
+
+	/// The number of sets of labels that were tried
+	pub sets: usize,
+
+	pub findings: Vec<Finding>,
+}
+
+/// The outcome of a rule for one set of labels, as returned by
+/// [Rule::check](super::rule::Rule::check)
+type Outcome = Option<bool>;
+
+/// Groups the `labels` by the `sets` matching them. The labels no set matches are left out.
+fn classes(labels: &[LabelId], sets: &[&LabelMatchSet]) -> Vec<Vec<LabelId>> {
+	let mut classes: BTreeMap<Vec<bool>, Vec<LabelId>> = BTreeMap::new();
+	labels.iter().for_each(|id| {
+		let matched: Vec<bool> = sets.iter().map(|set| set.matches_label(id).0).collect();
+		if matched.contains(&true) {
+			classes.entry(matched).or_default().push(id.clone());
+		}
+	});
+	let mut classes: Vec<Vec<LabelId>> = classes.into_values().collect();
+	classes.sort();
+	classes
+}
+
+/// Returns true if the rule does not fail.
+fn ok(outcome: Outcome) -> bool {
+	outcome != Some(false)
+}
+
+impl Analysis {
+	/// Analyze the enabled rules of the `specs` against all the sets of labels of the specs.
+	/// This fails if that makes more than [MAX_SETS] sets.
+	pub fn new(specs: &Specs) -> Result<Self> {
+		let rules: Vec<_> = specs.rules.iter().filter(|rule| !rule.disabled).collect();
+		let sets: Vec<&LabelMatchSet> =
+			rules.iter().flat_map(|rule| rule.spec.label_match_sets()).collect();
+
+		let mut labels: Vec<LabelId> = specs.label_names().keys().cloned().collect();
+		labels.sort();
+		let classes = classes(&labels, &sets);
+		let labels: Vec<LabelId> = labels
+			.into_iter()
+			.filter(|id| classes.iter().any(|class| class.contains(id)))
+			.collect();
+
+		// A set takes from 0 to all the labels of each class
+		let count = classes
+			.iter()
+			.try_fold(1_usize, |count, class| count.checked_mul(class.len() + 1))
+			.filter(|&count| count <= MAX_SETS);
+		let Some(count) = count else {
+			bail!(
+				"The {} labels of the specs make too many sets of labels, the analysis supports up to {MAX_SETS} sets",
+				labels.len()
+			)
+		};
+
+		// outcomes[set][rule], `set` telling how many labels of each class are part of the set
+		let outcomes: Vec<Vec<Outcome>> = (0..count)
+			.map(|set| {
+				let mut rest = set;
+				let ids: HashSet<LabelId> = classes
+					.iter()
+					.flat_map(|class| {
+						let taken = rest % (class.len() + 1);
+						rest /= class.len() + 1;
+						class[..taken].iter().cloned()
+					})
+					.collect();
+				rules.iter().map(|rule| rule.check(&ids, specs)).collect()
+			})
+			.collect();
+		log::debug!("Analyzed {} rules against {} sets of labels", rules.len(), outcomes.len());
+
+		let mut findings = Vec::new();
+		let applies = |r: usize| outcomes.iter().any(|o| o[r].is_some());
+		let passes = |r: usize| outcomes.iter().any(|o| o[r] == Some(true));
+
+		let mut sound = vec![true; rules.len()];
+		(0..rules.len()).for_each(|r| {
+			if rules[r].spec.when.is_some() && !applies(r) {
+				findings.push(Finding::Vacuous { rule: rules[r].name.clone() });
+				sound[r] = false;
+			} else if applies(r) && !passes(r) {
+				findings.push(Finding::Unsatisfiable { rule: rules[r].name.clone() });
+				sound[r] = false;
+			}
+		});
+
+		(0..rules.len()).filter(|&a| sound[a]).for_each(|a| {
+			(a + 1..rules.len()).filter(|&b| sound[b]).for_each(|b| {
+				let both_apply = outcomes.iter().filter(|o| o[a].is_some() && o[b].is_some());
+				let mut both_apply = both_apply.peekable();
+				if both_apply.peek().is_some() &&
+					!both_apply.any(|o| o[a] == Some(true) && o[b] == Some(true))
+				{
+					findings.push(Finding::Conflict {
+						rules: [rules[a].name.clone(), rules[b].name.clone()],
+					});
+				}
+			})
+		});
+
+		if !outcomes.iter().any(|o| o.iter().all(|&outcome| ok(outcome))) {
+			findings.push(Finding::NoValidSet);
+		}
+
+		// With conflicting rules, any rule looks implied by the others
+		if findings
+			.iter()
+			.any(|f| matches!(f, Finding::Conflict { .. } | Finding::NoValidSet))
+		{
+			return Ok(Self { labels, classes, sets: outcomes.len(), findings })
+		}
+
+		// A redundant rule no longer counts for the next ones, so that only one of two
+		// equivalent rules is reported
+		let mut kept = sound.clone();
+		(0..rules.len()).filter(|&r| sound[r]).for_each(|r| {
+			let others = |o: &Vec<Outcome>| {
+				(0..rules.len()).filter(|&i| i != r && kept[i]).all(|i| ok(o[i]))
+			};
+			if outcomes.iter().filter(|o| others(o)).all(|o| ok(o[r])) {
+				let implied_by = (0..rules.len())
+					.filter(|&i| i != r && kept[i])
+					.find(|&i| outcomes.iter().filter(|o| ok(o[i])).all(|o| ok(o[r])))
+					.map(|i| rules[i].name.clone());
+				findings.push(Finding::Redundant { rule: rules[r].name.clone(), implied_by });
+				kept[r] = false;
+			}
+		});
+
+		Ok(Self { labels, classes, sets: outcomes.len(), findings })
+	}
+
+	/// Returns the highest severity of the findings, `None` if there is nothing to report.
+	pub fn severity(&self) -> Option<Severity> {
+		self.findings.iter().map(|finding| finding.severity()).max()
+	}
+}
+
+/// The [AnalysisPrinter] renders an [Analysis] for humans.
+#[derive(Debug)]
+pub struct AnalysisPrinter<'a> {
+	analysis: &'a Analysis,
+	color: bool,
+}
+
+impl<'a> AnalysisPrinter<'a> {
+	pub fn new(analysis: &'a Analysis) -> Self {
+		Self { analysis, color: true }
+	}
+
+	pub fn with_color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	fn paint(&self, s: &str, c: &dyn color::Color) -> String {
+		if self.color {
+			format!("{}{s}{}", color::Fg(c), color::Fg(color::Reset))
+		} else {
+			s.to_string()
+		}
+	}
+
+	pub fn print(&self) {
+		println!(
+			"Analyzed {} sets of the {} labels",
+			self.analysis.sets,
+			self.analysis.labels.len()
+		);
+		self.analysis.findings.iter().for_each(|finding| {
+			let severity = finding.severity();
+			let level = match severity {
+				Severity::Error => self.paint(&severity.to_string(), &color::Red),
+				_ => self.paint(&severity.to_string(), &color::Yellow),
+			};
+			println!("{level}: {finding}");
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_analyze {
+	use super::*;
+	use crate::rllib::{rule::Rule, specs::Label};
+	use std::path::PathBuf;
+
+	/// The mini specs, with the labels A1 and A2, and the passed rules
+	fn analyze(rules: &str) -> Vec<Finding> {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		specs.rules = serde_yaml::from_str::<Vec<Rule>>(rules).unwrap();
+		Analysis::new(&specs).unwrap().findings
+	}
+
+	fn names(a: &str, b: &str) -> [String; 2] {
+		[a.to_string(), b.to_string()]
+	}
+
+	#[test]
+	fn test_nothing_to_report() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		let analysis = Analysis::new(&specs).unwrap();
+		assert_eq!(4, analysis.sets);
+		assert!(analysis.findings.is_empty());
+	}
+
+	#[test]
+	fn test_unsatisfiable_and_vacuous() {
+		let findings = analyze(
+			"
+- name: never
+  spec:
+    require: !one_of [ A1 ]
+    exclude: !some_of [ A* ]
+- name: skipped
+  spec:
+    when: !all [ !one_of [ A1 ], !none_of [ A1 ] ]
+    require: !one_of [ A2 ]
+",
+		);
+		assert!(findings.contains(&Finding::Unsatisfiable { rule: "never".to_string() }));
+		assert!(findings.contains(&Finding::Vacuous { rule: "skipped".to_string() }));
+		assert!(findings.contains(&Finding::NoValidSet));
+	}
+
+	#[test]
+	fn test_conflict() {
+		let findings = analyze(
+			"
+- name: need A1
+  spec:
+    require: !all_of [ A1 ]
+- name: no A
+  spec:
+    exclude: !some_of [ A* ]
+",
+		);
+		assert_eq!(
+			vec![Finding::Conflict { rules: names("need A1", "no A") }, Finding::NoValidSet],
+			findings
+		);
+	}
+
+	#[test]
+	fn test_conflict_when_both_apply() {
+		let findings = analyze(
+			"
+- name: A1 needs A2
+  spec:
+    when: !one_of [ A1 ]
+    require: !one_of [ A2 ]
+- name: A2 needs no A1
+  spec:
+    when: !one_of [ A2 ]
+    exclude: !some_of [ A1 ]
+",
+		);
+		assert_eq!(
+			vec![Finding::Conflict { rules: names("A1 needs A2", "A2 needs no A1") }],
+			findings
+		);
+	}
+
+	#[test]
+	fn test_redundant() {
+		let findings = analyze(
+			"
+- name: all of A
+  spec:
+    require: !all_of [ A* ]
+- name: some of A
+  spec:
+    require: !some_of [ A* ]
+- name: A1
+  spec:
+    require: !some_of [ A1 ]
+",
+		);
+		assert_eq!(
+			vec![
+				Finding::Redundant {
+					rule: "some of A".to_string(),
+					implied_by: Some("all of A".to_string())
+				},
+				Finding::Redundant {
+					rule: "A1".to_string(),
+					implied_by: Some("all of A".to_string())
+				},
+			],
+			findings
+		);
+	}
+
+	#[test]
+	fn test_classes() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		(0..32).for_each(|i| {
+			specs.insert_label(Label {
+				name: format!("X{i}-topic"),
+				description: String::new(),
+				color: String::new(),
+			});
+		});
+		specs.rules.push(
+			serde_yaml::from_str(
+				"
+name: some X
+spec:
+  require: !some_of [ X* ]
+",
+			)
+			.unwrap(),
+		);
+		let analysis = Analysis::new(&specs).unwrap();
+		assert_eq!(
+			vec![vec![LabelId::from("A1")], vec![LabelId::from("A2")]],
+			analysis.classes[..2]
+		);
+		assert_eq!(32, analysis.classes[2].len());
+		assert_eq!(2 * 2 * 33, analysis.sets);
+		assert!(analysis.findings.is_empty());
+	}
+
+	#[test]
+	fn test_too_many_sets() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		(0..16).for_each(|i| {
+			specs.insert_label(Label {
+				name: format!("X{i}-topic"),
+				description: String::new(),
+				color: String::new(),
+			});
+			specs.rules.push(
+				serde_yaml::from_str(&format!("name: X{i}\nspec:\n  require: !some_of [ X{i} ]"))
+					.unwrap(),
+			);
+		});
+		assert!(Analysis::new(&specs).is_err());
+	}
+
+	#[test]
+	fn test_severity() {
+		let findings = analyze(
+			"
+- name: A1
+  spec:
+    require: !some_of [ A1 ]
+- name: A1 again
+  spec:
+    require: !one_of [ A1 ]
+",
+		);
+		assert_eq!(
+			vec![Finding::Redundant {
+				rule: "A1".to_string(),
+				implied_by: Some("A1 again".to_string())
+			}],
+			findings
+		);
+		assert_eq!(Severity::Warning, findings[0].severity());
+	}
+}
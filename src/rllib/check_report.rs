@@ -0,0 +1,314 @@
+//! Definitions of [CheckReport], [RuleReport], [RuleOutcome] and [CheckReportPrinter].
+
+use super::{
+	condition::Condition,
+	label_match_set::LabelMatchSet,
+	parsed_label::LabelId,
+	rule::{Rule, RuleId},
+	severity::Severity,
+	specs::Specs,
+	suggest::Suggestion,
+	test_result::{ResultPrinter, TestResult},
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use tera::{Context, Tera};
+
+/// The outcome of the check of a single [Rule].
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOutcome {
+	/// The rule applied and the labels satisfy it
+	Passed,
+
+	/// The rule applied and the labels do not satisfy it
+	Failed,
+
+	/// The rule did not apply, usually because its `when` condition was not met
+	Skipped,
+
+	/// The rule is disabled
+	Disabled,
+
+	/// The rule was not checked since a rule of higher priority with `stop_on_failure` failed
+	Blocked,
+
+	/// The rule was left out by the tags or the [RuleFilter](super::rule_filter::RuleFilter)
+	Filtered,
+}
+
+/// What happened to a single [Rule] during a check, including the labels that matched each of
+/// its `when`, `require` and `exclude` clauses.
+#[derive(Debug, Serialize, Clone)]
+pub struct RuleReport {
+	/// Position of the rule in [Specs::rules]
+	pub index: usize,
+	pub id: Option<RuleId>,
+	pub name: String,
+	pub severity: Severity,
+	pub priority: u32,
+	pub outcome: RuleOutcome,
+
+	/// Labels matching the `when` clause, `None` if the rule has no such clause
+	pub when: Option<Vec<LabelId>>,
+
+	/// Labels matching the `require` clause, `None` if the rule has no such clause
+	pub require: Option<Vec<LabelId>>,
+
+	/// Labels matching the `exclude` clause, `None` if the rule has no such clause
+	pub exclude: Option<Vec<LabelId>>,
+
+	/// A hint telling the user how to satisfy the rule
+	pub tip: String,
+
+	/// The `message` of the rule rendered with the labels, only set when the rule failed
+	pub message: Option<String>,
+
+	/// The `help_url` of the rule
+	pub help_url: Option<String>,
+}
+
+/// The result of [Specs::run_checks]: one [RuleReport] for each [Rule] of the [Specs],
+/// the highest priority first.
+#[derive(Debug, Serialize, Clone)]
+pub struct CheckReport {
+	/// The labels that were checked, sorted
+	pub labels: Vec<LabelId>,
+
+	/// True if no rule of severity [CheckReport::fail_on] or higher failed
+	pub passed: bool,
+
+	/// The lowest [Severity] of the failed rules failing the check, see
+	/// [CheckReport::with_fail_on]
+	pub fail_on: Severity,
+
+	/// The highest [Severity] of the rules that failed, `None` if none failed
+	pub severity: Option<Severity>,
+	pub rules: Vec<RuleReport>,
+
+	/// The labels to change to satisfy all the rules, only computed on demand
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub suggestion: Option<Suggestion>,
+}
+
+/// Returns the `labels` matching any of the `sets`, sorted.
+fn matching_labels(
+	sets: Vec<&LabelMatchSet>,
+	labels: &HashSet<LabelId>,
+	specs: &Specs,
+) -> Vec<LabelId> {
+	let mut hits: Vec<LabelId> =
+		sets.iter().flat_map(|set| set.matching_labels(labels, specs)).collect();
+	hits.sort();
+	hits.dedup();
+	hits
+}
+
+/// Returns the labels of the specs matching any of the `sets` which are not part of `labels`,
+/// sorted.
+fn missing_labels(
+	sets: Vec<&LabelMatchSet>,
+	labels: &HashSet<LabelId>,
+	specs: &Specs,
+) -> Vec<LabelId> {
+	let mut missing: Vec<LabelId> = sets
+		.iter()
+		.flat_map(|set| specs.generate_reference_set(set, None))
+		.filter(|id| !labels.contains(id))
+		.collect();
+	missing.sort();
+	missing.dedup();
+	missing
+}
+
+impl RuleReport {
+	/// Build the [RuleReport] of `rule` for the passed `labels`. The `outcome` is computed
+	/// by the caller.
+	///
+	/// When the rule failed, its `message` is rendered with [Tera](https://keats.github.io/tera/)
+	/// and the following variables:
+	/// - `name` and `id`: those of the rule
+	/// - `labels`: the labels that were checked
+	/// - `when`, `require` and `exclude`: the labels matching each clause
+	/// - `missing`: the labels of the specs matching the `require` clause which are not present,
+	///   for the `one_of`, `some_of`, `all_of`, `at_least` and `exactly` conditions only
+	/// - `offending`: the labels matching the `exclude` clause
+	/// - `tip`: the generic hint
+	pub fn new(
+		index: usize,
+		rule: &Rule,
+		outcome: RuleOutcome,
+		labels: &HashSet<LabelId>,
+		specs: &Specs,
+	) -> Self {
+		let spec = &rule.spec;
+		let mut report = Self {
+			index,
+			id: rule.id.clone(),
+			name: rule.name.clone(),
+			severity: rule.severity,
+			priority: rule.priority,
+			outcome,
+			when: spec.when.as_ref().map(|t| matching_labels(t.label_match_sets(), labels, specs)),
+			require: spec
+				.require
+				.as_ref()
+				.map(|t| matching_labels(t.label_match_sets(), labels, specs)),
+			exclude: spec
+				.exclude
+				.as_ref()
+				.map(|t| matching_labels(t.label_match_sets(), labels, specs)),
+			tip: spec.to_user_tip(),
+			message: None,
+			help_url: rule.help_url.clone(),
+		};
+
+		if outcome == RuleOutcome::Failed {
+			report.message = rule.message.as_ref().map(|message| {
+				// Only the conditions asking for some labels tell which ones are missing
+				let missing = match spec.require.as_ref().map(|t| &t.0) {
+					Some(Condition::One(set) | Condition::Some(set) | Condition::All(set)) =>
+						missing_labels(vec![set], labels, specs),
+					Some(Condition::AtLeast(count) | Condition::Exactly(count)) =>
+						missing_labels(vec![&count.of], labels, specs),
+					_ => Vec::new(),
+				};
+				report.render(message, labels, &missing)
+			});
+		}
+		report
+	}
+
+	/// Render the `message` template of the rule. If that fails, the error is logged and the
+	/// template is returned as is.
+	fn render(&self, message: &str, labels: &HashSet<LabelId>, missing: &[LabelId]) -> String {
+		let mut labels: Vec<&LabelId> = labels.iter().collect();
+		labels.sort();
+
+		let mut context = Context::new();
+		context.insert("name", &self.name);
+		context.insert("id", &self.id);
+		context.insert("labels", &labels);
+		context.insert("when", &self.when);
+		context.insert("require", &self.require);
+		context.insert("exclude", &self.exclude);
+		context.insert("missing", missing);
+		context.insert("offending", &self.exclude.clone().unwrap_or_default());
+		context.insert("tip", &self.tip);
+
+		Tera::one_off(message, &context, false).unwrap_or_else(|e| {
+			log::warn!("Failed rendering the message of the rule `{}`: {e:?}", self.name);
+			message.to_string()
+		})
+	}
+
+	/// The text telling the user why the rule failed: its `message` if any, its tip otherwise.
+	pub fn text(&self) -> &str {
+		self.message.as_deref().unwrap_or(&self.tip)
+	}
+}
+
+impl CheckReport {
+	pub fn new(labels: &HashSet<LabelId>, rules: Vec<RuleReport>) -> Self {
+		let mut labels: Vec<LabelId> = labels.iter().cloned().collect();
+		labels.sort();
+		let severity = rules
+			.iter()
+			.filter(|r| r.outcome == RuleOutcome::Failed)
+			.map(|r| r.severity)
+			.max();
+		let fail_on = Severity::default();
+		let passed = severity < Some(fail_on);
+		Self { labels, passed, fail_on, severity, rules, suggestion: None }
+	}
+
+	/// Set the lowest [Severity] of the failed rules failing the check, `error` by default,
+	/// and update [CheckReport::passed] accordingly.
+	pub fn with_fail_on(mut self, fail_on: Severity) -> Self {
+		self.fail_on = fail_on;
+		self.passed = self.passes(fail_on);
+		self
+	}
+
+	/// Returns true if no rule of severity `fail_on` or higher failed.
+	pub fn passes(&self, fail_on: Severity) -> bool {
+		self.severity < Some(fail_on)
+	}
+
+	/// The exit code of the check: 0 if it [passes](Self::passes) at the `fail_on` level,
+	/// otherwise the [exit code](Severity::exit_code) of the most severe failure.
+	pub fn exit_code(&self, fail_on: Severity) -> i32 {
+		match self.severity {
+			Some(severity) if severity >= fail_on => severity.exit_code(),
+			_ => 0,
+		}
+	}
+
+	/// Returns the reports of the rules that failed.
+	pub fn failed(&self) -> impl Iterator<Item = &RuleReport> {
+		self.rules.iter().filter(|r| r.outcome == RuleOutcome::Failed)
+	}
+}
+
+impl From<RuleOutcome> for TestResult {
+	fn from(outcome: RuleOutcome) -> Self {
+		match outcome {
+			RuleOutcome::Passed => TestResult::Passed,
+			RuleOutcome::Failed => TestResult::Failed,
+			RuleOutcome::Skipped |
+			RuleOutcome::Disabled |
+			RuleOutcome::Blocked |
+			RuleOutcome::Filtered => TestResult::Skipped,
+		}
+	}
+}
+
+/// The [CheckReportPrinter] renders a [CheckReport] for humans.
+/// In verbose mode, it shows the outcome of every rule that ran, otherwise
+/// only the tips of the failed rules.
+#[derive(Debug)]
+pub struct CheckReportPrinter<'a> {
+	report: &'a CheckReport,
+	color: bool,
+	verbose: bool,
+}
+
+impl<'a> CheckReportPrinter<'a> {
+	pub fn new(report: &'a CheckReport) -> Self {
+		Self { report, color: true, verbose: false }
+	}
+
+	pub fn with_color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	pub fn with_verbose(mut self, verbose: bool) -> Self {
+		self.verbose = verbose;
+		self
+	}
+
+	pub fn print(&self) {
+		self.report
+			.rules
+			.iter()
+			.filter(|rule| rule.outcome != RuleOutcome::Filtered)
+			.for_each(|rule| {
+				if self.verbose {
+					let title = format!("{} | {}", &rule.name, rule.text());
+					ResultPrinter::new(&title, TestResult::from(rule.outcome))
+						.with_indent(8)
+						.with_color(self.color)
+						.print();
+				} else if rule.outcome == RuleOutcome::Failed {
+					match rule.severity {
+						Severity::Error => println!("{}", rule.text()),
+						severity => println!("{severity}: {}", rule.text()),
+					}
+					if let Some(url) = &rule.help_url {
+						println!("  help: {url}");
+					}
+				}
+			});
+	}
+}
@@ -1,15 +1,19 @@
 //! Definition of common types and helper functions.
 
 /// This helper function helps converting an interrable object of item convertible to
-/// strings, as comma separated string, making debugging easier.
+/// strings, as comma separated string, making debugging easier. The items are sorted so
+/// that the output of a [HashSet](std::collections::HashSet) does not change from a run to
+/// the next.
 /// ## example:
 /// ```
-/// let data = vec!["Hello", "World"];
-/// println!("{}", set_to_string(data));
-/// // Output: "Hello, World"
+/// # use ruled_labels::rllib::common::set_to_string;
+/// let data = vec!["World", "Hello"];
+/// assert_eq!("Hello, World", set_to_string(data));
 /// ```
 pub fn set_to_string<T: IntoIterator<Item = I>, I: ToString>(c: T) -> String {
-	c.into_iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+	let mut items: Vec<String> = c.into_iter().map(|e| e.to_string()).collect();
+	items.sort();
+	items.join(", ")
 }
 
 pub fn capitalize(s: &str) -> String {
@@ -0,0 +1,219 @@
+//! Definitions of [Coverage], [RuleCoverage] and [CoveragePrinter].
+//!
+//! The [Coverage] tells which rules of the [Specs] the test cases of a [TestReport] exercised.
+
+use super::{
+	check_report::RuleOutcome,
+	rule::RuleId,
+	specs::Specs,
+	test_report::TestReport,
+	test_result::{ResultPrinter, TestResult},
+};
+use serde::Serialize;
+
+/// How the test cases exercised a single enabled [Rule](super::rule::Rule).
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct RuleCoverage {
+	/// Position of the rule in [Specs::rules]
+	pub index: usize,
+	pub id: Option<RuleId>,
+	pub name: String,
+
+	/// Whether the rule has a `when` clause
+	pub when: bool,
+
+	/// The number of test cases the rule applied to
+	pub triggered: usize,
+	pub passed: usize,
+	pub failed: usize,
+
+	/// The number of test cases where the `when` of the rule was not met
+	pub skipped: usize,
+}
+
+impl RuleCoverage {
+	/// A rule is tested if at least one test case made it pass or fail.
+	pub fn tested(&self) -> bool {
+		self.triggered > 0
+	}
+
+	/// Returns true if the test cases took both branches of the `when`, or if there is no `when`.
+	pub fn when_covered(&self) -> bool {
+		!self.when || (self.triggered > 0 && self.skipped > 0)
+	}
+}
+
+/// The result of [Coverage::new].
+#[derive(Debug, Serialize, Clone)]
+pub struct Coverage {
+	/// The number of test cases that ran
+	pub tests: usize,
+
+	/// The enabled rules, in the order of the spec file
+	pub rules: Vec<RuleCoverage>,
+
+	/// The percentage of the enabled rules that were tested
+	pub percent: f64,
+}
+
+impl Coverage {
+	/// Compute the coverage of the enabled rules of the `specs` by the test cases that ran
+	/// in the `report`.
+	pub fn new(specs: &Specs, report: &TestReport) -> Self {
+		let mut rules: Vec<RuleCoverage> = specs
+			.rules
+			.iter()
+			.enumerate()
+			.filter(|(_, rule)| !rule.disabled)
+			.map(|(index, rule)| RuleCoverage {
+				index,
+				id: rule.id.clone(),
+				name: rule.name.clone(),
+				when: rule.spec.when.is_some(),
+				triggered: 0,
+				passed: 0,
+				failed: 0,
+				skipped: 0,
+			})
+			.collect();
+
+		let checks: Vec<_> = report.ran().filter_map(|test| test.report.as_ref()).collect();
+		checks.iter().flat_map(|check| &check.rules).for_each(|rule_report| {
+			if let Some(rule) = rules.iter_mut().find(|rule| rule.index == rule_report.index) {
+				match rule_report.outcome {
+					RuleOutcome::Passed => rule.passed += 1,
+					RuleOutcome::Failed => rule.failed += 1,
+					RuleOutcome::Skipped => rule.skipped += 1,
+					RuleOutcome::Disabled | RuleOutcome::Blocked | RuleOutcome::Filtered => {},
+				}
+				rule.triggered = rule.passed + rule.failed;
+			}
+		});
+
+		let percent = if rules.is_empty() {
+			100.0
+		} else {
+			100.0 * rules.iter().filter(|rule| rule.tested()).count() as f64 / rules.len() as f64
+		};
+		Self { tests: checks.len(), rules, percent }
+	}
+
+	/// Returns the rules no test case made pass or fail.
+	pub fn untested(&self) -> impl Iterator<Item = &RuleCoverage> {
+		self.rules.iter().filter(|rule| !rule.tested())
+	}
+}
+
+/// The [CoveragePrinter] renders a [Coverage] for humans.
+#[derive(Debug)]
+pub struct CoveragePrinter<'a> {
+	coverage: &'a Coverage,
+	color: bool,
+}
+
+impl<'a> CoveragePrinter<'a> {
+	pub fn new(coverage: &'a Coverage) -> Self {
+		Self { coverage, color: true }
+	}
+
+	pub fn with_color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	pub fn print(&self) {
+		let coverage = self.coverage;
+		println!(
+			"\nCoverage: {} of {} rules tested by {} tests ({:.1}%)",
+			coverage.rules.iter().filter(|rule| rule.tested()).count(),
+			coverage.rules.len(),
+			coverage.tests,
+			coverage.percent
+		);
+		coverage.rules.iter().for_each(|rule| {
+			let mut message =
+				format!("{}: {} passed, {} failed", rule.name, rule.passed, rule.failed);
+			if rule.when {
+				message.push_str(&format!(", {} skipped", rule.skipped));
+			}
+			if !rule.tested() {
+				message.push_str(", never tested");
+			} else if !rule.when_covered() {
+				message.push_str(", the `when` is always met");
+			}
+
+			ResultPrinter::new(&rule.name, TestResult::from(rule.tested()))
+				.with_message_passed(&message)
+				.with_message_failed(&message)
+				.with_indent(4)
+				.with_color(self.color)
+				.print();
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_coverage {
+	use super::*;
+	use crate::rllib::{rule::Rule, tests::Tests};
+	use std::path::PathBuf;
+
+	fn coverage(specs: &Specs, only: bool) -> Coverage {
+		let tests = Tests::load(&PathBuf::from("./tests/tests_pass.yaml")).unwrap();
+		let report = tests.run(specs, only, false, &None);
+		Coverage::new(specs, &report)
+	}
+
+	fn find<'a>(coverage: &'a Coverage, name: &str) -> &'a RuleCoverage {
+		coverage.rules.iter().find(|rule| rule.name == name).unwrap()
+	}
+
+	#[test]
+	fn test_counts() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let coverage = coverage(&specs, false);
+		assert_eq!(5, coverage.tests);
+		assert_eq!(10, coverage.rules.len());
+		assert_eq!(100.0, coverage.percent);
+
+		let rule = find(&coverage, "Require all of X");
+		assert!(!rule.when);
+		assert_eq!((5, 3, 2, 0), (rule.triggered, rule.passed, rule.failed, rule.skipped));
+		assert!(rule.when_covered());
+
+		let rule = find(&coverage, "Note Worthy implies no J label");
+		assert!(rule.when);
+		assert_eq!((4, 3, 1, 1), (rule.triggered, rule.passed, rule.failed, rule.skipped));
+		assert!(rule.when_covered());
+	}
+
+	#[test]
+	fn test_only() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let coverage = coverage(&specs, true);
+		assert_eq!(1, coverage.tests);
+		let rule = find(&coverage, "Note Worthy implies no J label");
+		assert_eq!((1, 0), (rule.triggered, rule.skipped));
+		assert!(!rule.when_covered());
+	}
+
+	#[test]
+	fn test_untested() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let rule: Rule = serde_yaml::from_str(
+			"name: D needs A
+spec:
+  when: !some_of [ D* ]
+  require: !some_of [ A* ]",
+		)
+		.unwrap();
+		specs.rules.push(rule);
+
+		let coverage = coverage(&specs, false);
+		let untested: Vec<&str> = coverage.untested().map(|rule| rule.name.as_str()).collect();
+		assert_eq!(vec!["D needs A"], untested);
+		assert_eq!(5, find(&coverage, "D needs A").skipped);
+		assert!(!find(&coverage, "D needs A").when_covered());
+		assert_eq!(100.0 * 10.0 / 11.0, coverage.percent);
+	}
+}
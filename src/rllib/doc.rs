@@ -0,0 +1,191 @@
+//! Generation of the documentation of a [Specs] using a [Tera](https://keats.github.io/tera/)
+//! template. The template bundled in `templates/template.md.tera` produces Markdown which can
+//! optionally be converted to HTML.
+
+use super::{
+	common::capitalize,
+	rule::{Rule, RuleId, Tag},
+	rule_spec::RuleSpec,
+	specs::{Label, Specs},
+};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
+use tera::Tera;
+
+/// The template used when the user does not provide one
+pub const DEFAULT_TEMPLATE: &str = include_str!("../../templates/template.md.tera");
+
+/// The labels sharing the same category according to the
+/// [LabelScheme](super::label_scheme::LabelScheme) of the [Specs]
+#[derive(Debug, Serialize)]
+pub struct DocCategory<'a> {
+	/// The category, empty for the labels without category
+	pub name: String,
+	pub labels: Vec<&'a Label>,
+}
+
+/// A [Rule] along with the human readable version of its spec
+#[derive(Debug, Serialize)]
+pub struct DocRule<'a> {
+	pub name: &'a str,
+	pub id: &'a Option<RuleId>,
+	pub description: &'a Option<String>,
+	pub disabled: bool,
+	pub tags: &'a Option<Vec<Tag>>,
+	pub spec: &'a RuleSpec,
+	pub tip: String,
+	pub help_url: &'a Option<String>,
+	pub when: Option<String>,
+	pub require: Option<String>,
+	pub exclude: Option<String>,
+}
+
+impl<'a> From<&'a Rule> for DocRule<'a> {
+	fn from(rule: &'a Rule) -> Self {
+		Self {
+			name: &rule.name,
+			id: &rule.id,
+			description: &rule.description,
+			disabled: rule.disabled,
+			tags: &rule.tags,
+			spec: &rule.spec,
+			tip: rule.spec.to_user_tip(),
+			help_url: &rule.help_url,
+			when: rule.spec.when.as_ref().map(|t| capitalize(&t.to_string())),
+			require: rule.spec.require.as_ref().map(|t| capitalize(&t.to_string())),
+			exclude: rule.spec.exclude.as_ref().map(|t| capitalize(&t.to_string())),
+		}
+	}
+}
+
+/// What the templates have access to
+#[derive(Debug, Serialize)]
+pub struct DocContext<'a> {
+	pub name: &'a str,
+	pub description: &'a str,
+	pub version: String,
+
+	/// All the labels, sorted by name
+	pub labels: Vec<&'a Label>,
+
+	/// The labels grouped by category, sorted by category
+	pub categories: Vec<DocCategory<'a>>,
+	pub rules: Vec<DocRule<'a>>,
+}
+
+impl<'a> From<&'a Specs> for DocContext<'a> {
+	fn from(specs: &'a Specs) -> Self {
+		let mut labels: Vec<&Label> = specs.labels().iter().collect();
+		labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+		let mut categories: BTreeMap<String, Vec<&Label>> = BTreeMap::new();
+		labels.iter().for_each(|label| {
+			let category = specs.label_id(&label.name).map(|id| id.category).unwrap_or_default();
+			categories.entry(category).or_default().push(label);
+		});
+
+		Self {
+			name: &specs.name,
+			description: &specs.description,
+			version: specs.version.to_string(),
+			categories: categories
+				.into_iter()
+				.map(|(name, labels)| DocCategory { name, labels })
+				.collect(),
+			labels,
+			rules: specs
+				.rules_by_priority()
+				.into_iter()
+				.map(|(_, rule)| DocRule::from(rule))
+				.collect(),
+		}
+	}
+}
+
+/// Render the documentation of the [Specs] as Markdown using the template
+/// located at `template` or the [DEFAULT_TEMPLATE].
+pub fn render_markdown(specs: &Specs, template: Option<&Path>) -> Result<String> {
+	let template = match template {
+		Some(path) => fs::read_to_string(path)
+			.with_context(|| format!("Failed reading the template {}", path.display()))?,
+		None => DEFAULT_TEMPLATE.to_string(),
+	};
+	let context = tera::Context::from_serialize(DocContext::from(specs))?;
+
+	Tera::one_off(&template, &context, false).context("Failed rendering the template")
+}
+
+/// Convert the Markdown produced by [render_markdown] into a standalone HTML page.
+pub fn markdown_to_html(title: &str, markdown: &str) -> String {
+	let parser = pulldown_cmark::Parser::new_ext(markdown, pulldown_cmark::Options::all());
+	let mut body = String::new();
+	pulldown_cmark::html::push_html(&mut body, parser);
+
+	format!(
+		"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{body}</body>\n</html>\n",
+		title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+	)
+}
+
+#[cfg(test)]
+mod test_doc {
+	use super::*;
+	use std::path::PathBuf;
+
+	fn specs() -> Specs {
+		Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap()
+	}
+
+	#[test]
+	fn test_context_groups_labels_by_category() {
+		let specs = specs();
+		let context = DocContext::from(&specs);
+
+		assert_eq!(specs.labels().len(), context.labels.len());
+		assert!(context.categories.windows(2).all(|w| w[0].name < w[1].name));
+		assert!(context
+			.categories
+			.iter()
+			.all(|c| c.labels.iter().all(|l| l.name.starts_with(&c.name))));
+	}
+
+	#[test]
+	fn test_context_groups_namespaced_labels() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_full_name.yaml")).unwrap();
+		let context = DocContext::from(&specs);
+		let names: Vec<&str> = context.categories.iter().map(|c| c.name.as_str()).collect();
+
+		assert_eq!(vec!["", "area/", "kind/"], names);
+		assert_eq!(2, context.categories[0].labels.len());
+	}
+
+	#[test]
+	fn test_render_markdown() {
+		let specs = specs();
+		let md = render_markdown(&specs, None).unwrap();
+
+		assert!(md.starts_with(&format!("# Label Documentation for {}", specs.name)));
+		assert!(md.contains("### Note Worthy need one Prio label"));
+		assert!(md.contains(&specs.rules[0].spec.to_user_tip()));
+	}
+
+	#[test]
+	fn test_render_custom_template() {
+		let path = std::env::temp_dir().join(format!("ruled-labels-{}.tera", std::process::id()));
+		fs::write(&path, "{{ name }}: {% for c in categories %}{{ c.name }}{% endfor %}").unwrap();
+		let md = render_markdown(&specs(), Some(&path)).unwrap();
+		fs::remove_file(&path).unwrap();
+
+		assert_eq!("chevdor/glabel: ABDJPX", md);
+	}
+
+	#[test]
+	fn test_markdown_to_html() {
+		let html = markdown_to_html("a <b>", "# Title\n\n- item\n");
+
+		assert!(html.contains("<title>a &lt;b&gt;</title>"));
+		assert!(html.contains("<h1>Title</h1>"));
+		assert!(html.contains("<li>item</li>"));
+	}
+}
@@ -0,0 +1,276 @@
+//! Generation of [Tests] from [Specs].
+//!
+//! For each enabled rule with an `id`, [generate] looks for the smallest sets of labels making the
+//! rule pass, fail or skip, and for the boundary cases of its `require` and `exclude` tokens: none,
+//! one or two matching labels, all of them and around the `n` of the counting tokens. Each test
+//! case is filtered on the id of its rule so it only checks this rule.
+//!
+//! As for the suggestions, the search stops at [DEFAULT_MAX_CHANGES] labels. The matching labels
+//! of the boundary cases are taken directly and do not count toward this limit.
+
+use super::{
+	condition::Condition,
+	label_match_set::LabelMatchSet,
+	parsed_label::LabelId,
+	rule::{Rule, RuleId},
+	rule_filter::RuleFilter,
+	severity::Severity,
+	specs::Specs,
+	suggest::{find_combination, DEFAULT_MAX_CHANGES},
+	tests::{TestSpec, TestSpecs, Tests},
+};
+use std::{
+	collections::{HashMap, HashSet},
+	path::Path,
+};
+
+/// Returns the set and the `n` of a condition, `None` for the conditions nesting other ones.
+fn boundary(condition: &Condition) -> Option<(&LabelMatchSet, Option<usize>)> {
+	match condition {
+		Condition::None(set) | Condition::One(set) | Condition::Some(set) | Condition::All(set) =>
+			Some((set, None)),
+		Condition::AtLeast(count) | Condition::AtMost(count) | Condition::Exactly(count) =>
+			Some((&count.of, Some(count.n))),
+		Condition::And(_) | Condition::Or(_) | Condition::Not(_) => None,
+	}
+}
+
+/// The numbers of matching labels worth testing, out of `size` labels of the specs matching
+/// the set.
+fn counts(n: Option<usize>, size: usize) -> Vec<usize> {
+	let mut counts: Vec<usize> = [0, 1, 2, size]
+		.into_iter()
+		.chain(n.into_iter().flat_map(|n| [n.saturating_sub(1), n, n + 1]))
+		.filter(|&k| k <= size)
+		.collect();
+	counts.sort();
+	counts.dedup();
+	counts
+}
+
+/// Generate the test cases of a single `rule` identified by `id`.
+fn rule_cases(
+	specs: &Specs,
+	names: &HashMap<LabelId, String>,
+	rule: &Rule,
+	id: &RuleId,
+) -> Vec<TestSpec> {
+	let spec = &rule.spec;
+	let sets = spec.label_match_sets();
+
+	// Only the labels matching the rule change its outcome
+	let mut relevant: Vec<&LabelId> = names
+		.keys()
+		.filter(|id| sets.iter().any(|set| set.matches_label(id).0))
+		.collect();
+	relevant.sort();
+
+	// The `base` labels along with the `pool` labels at the `positions`
+	let pick = |base: &[&LabelId], pool: &[&LabelId], positions: &[usize]| -> HashSet<LabelId> {
+		base.iter()
+			.cloned()
+			.chain(positions.iter().map(|&i| pool[i]))
+			.cloned()
+			.collect()
+	};
+	// The smallest set of labels made of the `base` labels and some of the `pool` labels
+	// for which `f` returns true
+	let smallest = |base: &[&LabelId], pool: &[&LabelId], f: &dyn Fn(Option<bool>) -> bool| {
+		(0..=pool.len().min(DEFAULT_MAX_CHANGES))
+			.find_map(|k| {
+				find_combination(pool.len(), k, &mut |positions| {
+					f(rule.check(&pick(base, pool, positions), specs))
+				})
+			})
+			.map(|positions| pick(base, pool, &positions))
+	};
+
+	let mut cases: Vec<(String, Option<HashSet<LabelId>>)> = vec![
+		("passes".to_string(), smallest(&[], &relevant, &|outcome| outcome == Some(true))),
+		("fails".to_string(), smallest(&[], &relevant, &|outcome| outcome == Some(false))),
+	];
+	if spec.when.is_some() {
+		cases.push(("when not met".to_string(), smallest(&[], &relevant, &|o| o.is_none())));
+	}
+
+	[
+		("require", spec.require.as_ref().and_then(|token| boundary(&token.0))),
+		("exclude", spec.exclude.as_ref().and_then(|token| boundary(&token.0))),
+	]
+	.into_iter()
+	.filter_map(|(clause, boundary)| boundary.map(|(set, n)| (clause, set, n)))
+	.for_each(|(clause, set, n)| {
+		// The first `k` labels matching the set, with the other labels the rule needs to apply
+		let (matching, others): (Vec<&LabelId>, Vec<&LabelId>) =
+			relevant.iter().partition(|id| set.matches_label(id).0);
+		counts(n, matching.len()).into_iter().for_each(|k| {
+			let labels = smallest(&matching[..k], &others, &|outcome| outcome.is_some());
+			cases.push((format!("{clause} with {k} of {set}"), labels));
+		});
+	});
+
+	let filter = Some(RuleFilter { id: vec![id.clone()] });
+	// The test cases fail along with the rule, whatever its severity
+	let fail_on = (rule.severity != Severity::default()).then_some(rule.severity);
+	let mut seen: Vec<Vec<String>> = Vec::new();
+	cases
+		.into_iter()
+		.filter_map(|(what, labels)| {
+			let Some(labels) = labels else {
+				log::debug!("No set of labels for `{id} - {what}`");
+				return None
+			};
+			let mut label_names: Vec<String> = labels.iter().map(|id| names[id].clone()).collect();
+			label_names.sort();
+			if seen.contains(&label_names) {
+				return None
+			}
+			seen.push(label_names.clone());
+
+			Some(TestSpec {
+				name: format!("{id} - {what}"),
+				description: None,
+				filter: filter.clone(),
+				labels: label_names,
+				skip: None,
+				only: None,
+				fail_on,
+				expected: rule.check(&labels, specs) != Some(false),
+			})
+		})
+		.collect()
+}
+
+/// Generate [Tests] for the enabled rules of the `specs`, the generated file referring to the
+/// specs as `spec_file`. The rules without `id` cannot be filtered and are left out.
+pub fn generate(specs: &Specs, spec_file: &Path) -> Tests {
+	let test_specs = specs
+		.rules
+		.iter()
+		.filter(|rule| !rule.disabled)
+		.flat_map(|rule| match &rule.id {
+			Some(id) => rule_cases(specs, specs.label_names(), rule, id),
+			None => {
+				log::debug!("Skipping the rule `{}` which has no id", rule.name);
+				vec![]
+			},
+		})
+		.collect();
+
+	Tests {
+		name: format!("Generated tests for {}", specs.name),
+		spec_file: spec_file.to_path_buf(),
+		specs: TestSpecs { specs: test_specs },
+	}
+}
+
+#[cfg(test)]
+mod test_gen_tests {
+	use super::*;
+	use crate::rllib::specs::Label;
+	use std::path::PathBuf;
+
+	fn generate_priority() -> (Specs, Tests) {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_priority.yaml")).unwrap();
+		let tests = generate(&specs, &PathBuf::from("specs_priority.yaml"));
+		(specs, tests)
+	}
+
+	#[test]
+	fn test_counts() {
+		assert_eq!(vec![0, 1, 2, 3], counts(None, 3));
+		assert_eq!(vec![0, 1], counts(None, 1));
+		assert_eq!(vec![0, 1, 2, 3, 4, 6], counts(Some(3), 6));
+		assert_eq!(vec![0, 1, 2], counts(Some(5), 2));
+	}
+
+	#[test]
+	fn test_cases() {
+		let (_, tests) = generate_priority();
+		let cases: Vec<(&str, Vec<&str>, bool)> = tests
+			.specs
+			.specs
+			.iter()
+			.filter(|t| t.name.starts_with("one_b"))
+			.map(|t| (t.name.as_str(), t.labels.iter().map(|l| l.as_str()).collect(), t.expected))
+			.collect();
+		assert_eq!(
+			vec![
+				("one_b - passes", vec!["B1-note_worthy"], true),
+				("one_b - fails", vec![], false),
+				("one_b - require with 2 of B*", vec!["B1-note_worthy", "B2-silent"], false),
+			],
+			cases
+		);
+		assert!(tests
+			.specs
+			.specs
+			.iter()
+			.any(|t| t.name == "b1_needs_p - when not met" && t.expected));
+	}
+
+	#[test]
+	fn test_filtered_on_the_rule() {
+		let (_, tests) = generate_priority();
+		assert!(tests.specs.specs.iter().all(|t| {
+			let id = t.name.split(" - ").next().unwrap();
+			t.filter.as_ref().unwrap().id == vec![id.to_string()]
+		}));
+	}
+
+	#[test]
+	fn test_generated_tests_pass() {
+		let (specs, tests) = generate_priority();
+		let yaml = serde_yaml::to_string(&tests).unwrap();
+		let tests: Tests = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!(PathBuf::from("specs_priority.yaml"), tests.spec_file);
+		assert!(tests.run(&specs, false, false, &None).passed);
+	}
+
+	#[test]
+	fn test_severity() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_severity.yaml")).unwrap();
+		let tests = generate(&specs, &PathBuf::from("specs_severity.yaml"));
+		let case = |name: &str| tests.specs.specs.iter().find(|t| t.name == name).unwrap();
+
+		assert!(!case("b1_needs_p - fails").expected);
+		assert_eq!(Some(Severity::Warning), case("b1_needs_p - fails").fail_on);
+		assert!(case("b1_needs_p - passes").expected);
+		assert!(!case("no_draft - fails").expected);
+		assert_eq!(None, case("one_b - fails").fail_on);
+		assert!(tests.run(&specs, false, false, &None).passed);
+	}
+
+	#[test]
+	fn test_boundary_beyond_the_search_limit() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		(0..2 * DEFAULT_MAX_CHANGES).for_each(|i| {
+			specs.insert_label(Label {
+				name: format!("X{i}-topic"),
+				description: String::new(),
+				color: String::new(),
+			});
+		});
+		specs.rules = serde_yaml::from_str(
+			"
+- name: All topics
+  id: all_x
+  spec:
+    require: !all_of [ X* ]
+",
+		)
+		.unwrap();
+
+		let tests = generate(&specs, &PathBuf::from("specs_mini.yaml"));
+		let all = tests.specs.specs.iter().find(|t| t.name == "all_x - require with 10 of X*");
+		assert_eq!(10, all.unwrap().labels.len());
+		assert!(all.unwrap().expected);
+		assert!(tests.run(&specs, false, false, &None).passed);
+	}
+
+	#[test]
+	fn test_rules_without_id() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		assert!(generate(&specs, &PathBuf::from("specs_mini.yaml")).specs.specs.is_empty());
+	}
+}
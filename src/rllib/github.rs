@@ -0,0 +1,655 @@
+//! Access to the Github API. The [GithubApi] trait describes what `ruled-labels` needs from
+//! Github and [GithubClient] implements it on top of `hubcaps`. The API host can be changed,
+//! which allows testing against a local server.
+//!
+//! [report], [report_comment] and [report_status] post a [CheckReport] back to Github.
+//! [plan_fix] and [apply_fix] change the labels so that they satisfy the rules.
+
+use super::{
+	check_report::CheckReport,
+	severity::Severity,
+	specs::Specs,
+	suggest::{ChangeAction, Suggestion},
+};
+use anyhow::{anyhow, bail, Context, Result};
+use hubcaps::{
+	comments::CommentOptions,
+	statuses::{State, StatusOptions},
+	Credentials, Github,
+};
+use serde::Deserialize;
+use std::{
+	fmt::Display,
+	str::FromStr,
+	sync::{Mutex, PoisonError},
+};
+use tokio::runtime::{Builder, Runtime};
+
+/// The default Github API host
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// This hidden marker allows finding the comment of `ruled-labels` among the others.
+pub const COMMENT_MARKER: &str = "<!-- ruled-labels -->";
+
+/// The number of comments fetched per request, the maximum allowed by Github.
+const COMMENTS_PER_PAGE: usize = 100;
+
+/// The context of the commit status set by `ruled-labels`.
+pub const STATUS_CONTEXT: &str = "ruled-labels";
+
+/// Where the result of a check can be posted on Github
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportTarget {
+	/// A single comment, created on failure and updated on the next runs
+	GithubComment,
+
+	/// A commit status on the head of the pull request
+	GithubStatus,
+}
+
+/// A comment of an issue or pull request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct IssueComment {
+	pub id: u64,
+	pub body: String,
+}
+
+/// A Github repository, given as `owner/name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+	pub owner: String,
+	pub name: String,
+}
+
+impl FromStr for Repo {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.split_once('/') {
+			Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') =>
+				Ok(Self { owner: owner.to_string(), name: name.to_string() }),
+			_ => Err(format!("Invalid repository: {s}, expected owner/name")),
+		}
+	}
+}
+
+impl Display for Repo {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_fmt(format_args!("{}/{}", self.owner, self.name))
+	}
+}
+
+/// What `ruled-labels` needs from the Github API.
+/// Pull requests are issues for Github so `number` can be either.
+pub trait GithubApi {
+	/// Returns the names of the labels currently set on the issue or pull request `number`.
+	fn labels(&self, repo: &Repo, number: u64) -> Result<Vec<String>>;
+
+	/// Returns the comments of the issue or pull request `number`.
+	fn comments(&self, repo: &Repo, number: u64) -> Result<Vec<IssueComment>>;
+
+	/// Add a new comment to the issue or pull request `number`.
+	fn create_comment(&self, repo: &Repo, number: u64, body: &str) -> Result<()>;
+
+	/// Replace the body of an existing comment.
+	fn update_comment(&self, repo: &Repo, comment_id: u64, body: &str) -> Result<()>;
+
+	/// Returns the sha of the head commit of the pull request `number`.
+	fn head_sha(&self, repo: &Repo, number: u64) -> Result<String>;
+
+	/// Set the [STATUS_CONTEXT] commit status of the commit `sha`.
+	fn create_status(&self, repo: &Repo, sha: &str, passed: bool, description: &str) -> Result<()>;
+
+	/// Add the labels named `names` to the issue or pull request `number`.
+	fn add_labels(&self, repo: &Repo, number: u64, names: &[String]) -> Result<()>;
+
+	/// Remove the label named `name` from the issue or pull request `number`.
+	fn remove_label(&self, repo: &Repo, number: u64, name: &str) -> Result<()>;
+}
+
+/// A change of the labels of an issue or pull request, see [plan_fix].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelMutation {
+	Add(String),
+	Remove(String),
+}
+
+impl Display for LabelMutation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			LabelMutation::Add(name) => f.write_fmt(format_args!("add label `{name}`")),
+			LabelMutation::Remove(name) => f.write_fmt(format_args!("remove label `{name}`")),
+		}
+	}
+}
+
+/// The [GithubApi] implementation based on `hubcaps`.
+pub struct GithubClient {
+	github: Github,
+	runtime: Mutex<Runtime>,
+	api_url: String,
+	token: Option<String>,
+}
+
+impl GithubClient {
+	/// Create a new client for the API located at `api_url`, using the optional `token`.
+	pub fn new(api_url: &str, token: Option<String>) -> Result<Self> {
+		let agent = format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+		let api_url = api_url.trim_end_matches('/').to_string();
+		let github = Github::host(&api_url, agent, token.clone().map(Credentials::Token))
+			.context("Failed creating the Github client")?;
+		let runtime = Builder::new()
+			.basic_scheduler()
+			.enable_all()
+			.build()
+			.context("Failed creating the async runtime")?;
+
+		Ok(Self { github, runtime: Mutex::new(runtime), api_url, token })
+	}
+
+	/// The runtime is only driven by `Runtime::block_on`, hence the `Mutex`. A panic of
+	/// a previous call leaves the runtime usable so the lock being poisoned does not matter.
+	fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+		self.runtime.lock().unwrap_or_else(PoisonError::into_inner).block_on(future)
+	}
+
+	/// A request to the API, for the calls `hubcaps` does not support.
+	fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+		let request = reqwest::Client::new()
+			.request(method, url)
+			.header(
+				"User-Agent",
+				format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+			)
+			.header("Accept", "application/vnd.github.v3+json");
+		match &self.token {
+			Some(token) => request.header("Authorization", format!("token {token}")),
+			None => request,
+		}
+	}
+}
+
+impl GithubApi for GithubClient {
+	fn labels(&self, repo: &Repo, number: u64) -> Result<Vec<String>> {
+		log::debug!("Fetching labels of {repo}#{number}");
+		let issue = self
+			.block_on(self.github.repo(&repo.owner, &repo.name).issues().get(number).get())
+			.map_err(|e| anyhow!("Failed fetching {repo}#{number}: {e}"))?;
+
+		Ok(issue.labels.into_iter().map(|l| l.name).collect())
+	}
+
+	/// `hubcaps` only returns the first page of the comments so we go through the pages ourselves.
+	fn comments(&self, repo: &Repo, number: u64) -> Result<Vec<IssueComment>> {
+		let mut comments = Vec::new();
+		for page in 1.. {
+			log::debug!("Fetching page {page} of the comments of {repo}#{number}");
+			let url = format!(
+				"{}/repos/{}/{}/issues/{number}/comments?per_page={COMMENTS_PER_PAGE}&page={page}",
+				self.api_url, repo.owner, repo.name
+			);
+			let body = self
+				.block_on(async {
+					let response = self.request(reqwest::Method::GET, &url).send().await?;
+					response.error_for_status()?.bytes().await
+				})
+				.with_context(|| format!("Failed fetching the comments of {repo}#{number}"))?;
+			let page: Vec<IssueComment> = serde_json::from_slice(&body)
+				.with_context(|| format!("Invalid comments for {repo}#{number}"))?;
+
+			let last = page.len() < COMMENTS_PER_PAGE;
+			comments.extend(page);
+			if last {
+				break
+			}
+		}
+		Ok(comments)
+	}
+
+	fn create_comment(&self, repo: &Repo, number: u64, body: &str) -> Result<()> {
+		log::debug!("Commenting on {repo}#{number}");
+		self.block_on(
+			self.github
+				.repo(&repo.owner, &repo.name)
+				.issues()
+				.get(number)
+				.comments()
+				.create(&CommentOptions { body: body.to_string() }),
+		)
+		.map_err(|e| anyhow!("Failed commenting on {repo}#{number}: {e}"))?;
+		Ok(())
+	}
+
+	/// `hubcaps` cannot edit comments so we send the request ourselves.
+	fn update_comment(&self, repo: &Repo, comment_id: u64, body: &str) -> Result<()> {
+		log::debug!("Updating comment {comment_id} of {repo}");
+		let url = format!(
+			"{}/repos/{}/{}/issues/comments/{comment_id}",
+			self.api_url, repo.owner, repo.name
+		);
+		let request = self
+			.request(reqwest::Method::PATCH, &url)
+			.body(serde_json::json!({ "body": body }).to_string());
+
+		let response = self
+			.block_on(request.send())
+			.with_context(|| format!("Failed updating comment {comment_id} of {repo}"))?;
+		if !response.status().is_success() {
+			bail!("Failed updating comment {comment_id} of {repo}: {}", response.status())
+		}
+		Ok(())
+	}
+
+	fn head_sha(&self, repo: &Repo, number: u64) -> Result<String> {
+		let pull = self
+			.block_on(self.github.repo(&repo.owner, &repo.name).pulls().get(number).get())
+			.map_err(|e| anyhow!("Failed fetching the pull request {repo}#{number}: {e}"))?;
+		Ok(pull.head.sha)
+	}
+
+	fn create_status(&self, repo: &Repo, sha: &str, passed: bool, description: &str) -> Result<()> {
+		log::debug!("Setting the status of {repo}@{sha}");
+		let state = if passed { State::Success } else { State::Failure };
+		let options = StatusOptions::builder(state)
+			.description(description)
+			.context(STATUS_CONTEXT)
+			.build();
+		self.block_on(self.github.repo(&repo.owner, &repo.name).statuses().create(sha, &options))
+			.map_err(|e| anyhow!("Failed setting the status of {repo}@{sha}: {e}"))?;
+		Ok(())
+	}
+
+	fn add_labels(&self, repo: &Repo, number: u64, names: &[String]) -> Result<()> {
+		log::debug!("Adding labels {names:?} to {repo}#{number}");
+		let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+		self.block_on(
+			self.github
+				.repo(&repo.owner, &repo.name)
+				.issues()
+				.get(number)
+				.labels()
+				.add(names),
+		)
+		.map_err(|e| anyhow!("Failed adding labels to {repo}#{number}: {e}"))?;
+		Ok(())
+	}
+
+	fn remove_label(&self, repo: &Repo, number: u64, name: &str) -> Result<()> {
+		log::debug!("Removing label {name} from {repo}#{number}");
+		self.block_on(
+			self.github
+				.repo(&repo.owner, &repo.name)
+				.issues()
+				.get(number)
+				.labels()
+				.remove(name),
+		)
+		.map_err(|e| anyhow!("Failed removing the label {name} from {repo}#{number}: {e}"))?;
+		Ok(())
+	}
+}
+
+/// Turn a [Suggestion] into the changes to apply to the labels `names` of an issue or pull
+/// request. As a safety measure, only the labels defined in the specs may be removed.
+pub fn plan_fix(
+	suggestion: &Suggestion,
+	names: &[String],
+	specs: &Specs,
+) -> Result<Vec<LabelMutation>> {
+	let Some(changes) = &suggestion.changes else {
+		bail!("No set of labels satisfies all the rules, there is nothing to fix")
+	};
+
+	let mut mutations = Vec::new();
+	for change in changes {
+		match change.action {
+			ChangeAction::Add => match &change.name {
+				Some(name) => mutations.push(LabelMutation::Add(name.clone())),
+				None => bail!("Cannot add the label {}, it is not defined in the specs", change.id),
+			},
+			ChangeAction::Remove => {
+				let matching = names
+					.iter()
+					.filter(|name| specs.label_id(name).ok().as_ref() == Some(&change.id));
+				for name in matching {
+					if !specs.labels().iter().any(|label| &label.name == name) {
+						bail!("Refusing to remove the label `{name}` which is not defined in the specs")
+					}
+					mutations.push(LabelMutation::Remove(name.clone()));
+				}
+			},
+		}
+	}
+	Ok(mutations)
+}
+
+/// Apply the `mutations` to the issue or pull request `number`.
+pub fn apply_fix(
+	api: &dyn GithubApi,
+	repo: &Repo,
+	number: u64,
+	mutations: &[LabelMutation],
+) -> Result<()> {
+	mutations.iter().try_for_each(|mutation| match mutation {
+		LabelMutation::Remove(name) => api.remove_label(repo, number, name),
+		LabelMutation::Add(_) => Ok(()),
+	})?;
+
+	let added: Vec<String> = mutations
+		.iter()
+		.filter_map(|mutation| match mutation {
+			LabelMutation::Add(name) => Some(name.clone()),
+			LabelMutation::Remove(_) => None,
+		})
+		.collect();
+	if added.is_empty() {
+		return Ok(())
+	}
+	api.add_labels(repo, number, &added)
+}
+
+/// Render a [CheckReport] as the markdown body of our sticky comment. The check passes
+/// if no rule of severity `fail_on` or higher failed.
+pub fn comment_body(report: &CheckReport, specs: &Specs, fail_on: Severity) -> String {
+	let mut body = format!("{COMMENT_MARKER}\n");
+	let passed = report.passes(fail_on);
+	if passed && report.failed().count() == 0 {
+		body.push_str(&format!(
+			"### ✅ Labels check passed\n\nThe labels satisfy all the rules of `{}` v{}.\n",
+			specs.name, specs.version
+		));
+		return body
+	}
+
+	if passed {
+		body.push_str(&format!(
+			"### ✅ Labels check passed\n\nThe labels do not satisfy the following non blocking rules of `{}` v{}:\n\n",
+			specs.name, specs.version
+		));
+	} else {
+		body.push_str(&format!(
+			"### ❌ Labels check failed\n\nThe labels do not satisfy the following rules of `{}` v{}:\n\n",
+			specs.name, specs.version
+		));
+	}
+	report.failed().for_each(|rule| {
+		let help = match &rule.help_url {
+			Some(url) => format!(" ([help]({url}))"),
+			None => String::new(),
+		};
+		match rule.severity {
+			Severity::Error =>
+				body.push_str(&format!("- **{}**: {}{help}\n", rule.name, rule.text())),
+			severity =>
+				body.push_str(&format!("- **{}** ({severity}): {}{help}\n", rule.name, rule.text())),
+		}
+	});
+	body
+}
+
+/// Create or update the sticky comment of `ruled-labels` on the issue or pull request
+/// `number`. If the check passed and there is no comment yet, nothing is posted.
+pub fn report_comment(
+	api: &dyn GithubApi,
+	repo: &Repo,
+	number: u64,
+	report: &CheckReport,
+	specs: &Specs,
+	fail_on: Severity,
+) -> Result<()> {
+	let body = comment_body(report, specs, fail_on);
+	let existing = api
+		.comments(repo, number)?
+		.into_iter()
+		.find(|c| c.body.contains(COMMENT_MARKER));
+
+	match existing {
+		Some(comment) if comment.body == body => Ok(()),
+		Some(comment) => api.update_comment(repo, comment.id, &body),
+		None if report.passes(fail_on) => Ok(()),
+		None => api.create_comment(repo, number, &body),
+	}
+}
+
+/// Post the report to the [ReportTarget]. A commit status can only be set for a pull request.
+/// As for the exit code, the check fails if a rule of severity `fail_on` or higher failed.
+pub fn report(
+	api: &dyn GithubApi,
+	target: ReportTarget,
+	repo: &Repo,
+	number: u64,
+	report: &CheckReport,
+	specs: &Specs,
+	fail_on: Severity,
+) -> Result<()> {
+	match target {
+		ReportTarget::GithubComment => report_comment(api, repo, number, report, specs, fail_on),
+		ReportTarget::GithubStatus => report_status(api, repo, number, report, fail_on),
+	}
+}
+
+/// Set the commit status of the head of the pull request `number` according to the report.
+pub fn report_status(
+	api: &dyn GithubApi,
+	repo: &Repo,
+	number: u64,
+	report: &CheckReport,
+	fail_on: Severity,
+) -> Result<()> {
+	let sha = api.head_sha(repo, number)?;
+	let passed = report.passes(fail_on);
+	let description = match (passed, report.failed().count()) {
+		(_, 0) => "All rules passed".to_string(),
+		(true, 1) => "1 non blocking rule failed".to_string(),
+		(true, n) => format!("{n} non blocking rules failed"),
+		(false, 1) => "1 rule failed".to_string(),
+		(false, n) => format!("{n} rules failed"),
+	};
+	api.create_status(repo, &sha, passed, &description)
+}
+
+#[cfg(test)]
+mod test_github {
+	use super::*;
+
+	#[test]
+	fn test_repo_from_str() {
+		let repo = Repo::from_str("chevdor/ruled_labels").unwrap();
+		assert_eq!("chevdor", repo.owner);
+		assert_eq!("ruled_labels", repo.name);
+		assert_eq!("chevdor/ruled_labels", repo.to_string());
+
+		assert!(Repo::from_str("chevdor").is_err());
+		assert!(Repo::from_str("/ruled_labels").is_err());
+		assert!(Repo::from_str("chevdor/ruled/labels").is_err());
+	}
+
+	/// A [GithubApi] keeping everything in memory and recording the calls that modify Github.
+	#[derive(Default)]
+	struct MockApi {
+		labels: Vec<&'static str>,
+		comments: Vec<IssueComment>,
+		calls: std::cell::RefCell<Vec<String>>,
+	}
+
+	impl GithubApi for MockApi {
+		fn labels(&self, _repo: &Repo, _number: u64) -> Result<Vec<String>> {
+			Ok(self.labels.iter().map(|s| s.to_string()).collect())
+		}
+
+		fn comments(&self, _repo: &Repo, _number: u64) -> Result<Vec<IssueComment>> {
+			Ok(self.comments.clone())
+		}
+
+		fn create_comment(&self, _repo: &Repo, number: u64, _body: &str) -> Result<()> {
+			self.calls.borrow_mut().push(format!("create_comment {number}"));
+			Ok(())
+		}
+
+		fn update_comment(&self, _repo: &Repo, comment_id: u64, _body: &str) -> Result<()> {
+			self.calls.borrow_mut().push(format!("update_comment {comment_id}"));
+			Ok(())
+		}
+
+		fn head_sha(&self, _repo: &Repo, _number: u64) -> Result<String> {
+			Ok("abc123".to_string())
+		}
+
+		fn create_status(
+			&self,
+			_repo: &Repo,
+			sha: &str,
+			passed: bool,
+			description: &str,
+		) -> Result<()> {
+			self.calls
+				.borrow_mut()
+				.push(format!("create_status {sha} {passed} {description}"));
+			Ok(())
+		}
+
+		fn add_labels(&self, _repo: &Repo, number: u64, names: &[String]) -> Result<()> {
+			self.calls.borrow_mut().push(format!("add_labels {number} {}", names.join(",")));
+			Ok(())
+		}
+
+		fn remove_label(&self, _repo: &Repo, number: u64, name: &str) -> Result<()> {
+			self.calls.borrow_mut().push(format!("remove_label {number} {name}"));
+			Ok(())
+		}
+	}
+
+	fn check(labels: &[&str]) -> (Specs, CheckReport) {
+		let specs = Specs::load(&std::path::PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let labels = specs.label_ids(labels).unwrap();
+		let report = specs.run_checks(&labels, true, None, &None);
+		(specs, report)
+	}
+
+	#[test]
+	fn test_comment_body() {
+		let (specs, report) = check(&["B1", "X1"]);
+		let body = comment_body(&report, &specs, Severity::Error);
+
+		assert!(body.starts_with(COMMENT_MARKER));
+		assert!(body.contains("Labels check failed"));
+		assert!(body.contains("- **Note Worthy need one Prio label**: "));
+	}
+
+	#[test]
+	fn test_report_comment_creates_a_comment_on_failure() {
+		let (specs, report) = check(&["B1", "X1"]);
+		let api = MockApi::default();
+		report_comment(&api, &Repo::from_str("o/r").unwrap(), 42, &report, &specs, Severity::Error)
+			.unwrap();
+
+		assert_eq!(vec!["create_comment 42"], *api.calls.borrow());
+	}
+
+	#[test]
+	fn test_report_comment_does_not_comment_on_success() {
+		let (specs, report) = check(&["B1", "X1", "X2", "X3", "P2"]);
+		assert!(report.passed);
+		let api = MockApi::default();
+		report_comment(&api, &Repo::from_str("o/r").unwrap(), 42, &report, &specs, Severity::Error)
+			.unwrap();
+
+		assert!(api.calls.borrow().is_empty());
+	}
+
+	#[test]
+	fn test_report_comment_updates_the_existing_comment() {
+		let (specs, report) = check(&["B1", "X1", "X2", "X3", "P2"]);
+		let api = MockApi {
+			comments: vec![
+				IssueComment { id: 1, body: "LGTM".to_string() },
+				IssueComment { id: 2, body: format!("{COMMENT_MARKER}\nfailed") },
+			],
+			..Default::default()
+		};
+		report_comment(&api, &Repo::from_str("o/r").unwrap(), 42, &report, &specs, Severity::Error)
+			.unwrap();
+
+		assert_eq!(vec!["update_comment 2"], *api.calls.borrow());
+	}
+
+	#[test]
+	fn test_report_status() {
+		let (_specs, report) = check(&["B1", "X1"]);
+		let api = MockApi::default();
+		report_status(&api, &Repo::from_str("o/r").unwrap(), 42, &report, Severity::Error).unwrap();
+
+		assert_eq!(vec!["create_status abc123 false 2 rules failed"], *api.calls.borrow());
+	}
+
+	#[test]
+	fn test_comment_body_message() {
+		let specs = Specs::load(&std::path::PathBuf::from("./tests/specs_message.yaml")).unwrap();
+		let report = specs.run_checks(&specs.label_ids(&["B1"]).unwrap(), true, None, &None);
+		let body = comment_body(&report, &specs, Severity::Error);
+
+		assert!(body.contains(
+			"- **Note worthy needs a priority**: B1 needs a priority, add one of P1, P2 ([help](https://example.com/labels#priority))"
+		));
+	}
+
+	#[test]
+	fn test_plan_and_apply_fix() {
+		let specs = Specs::load(&std::path::PathBuf::from("./tests/specs_priority.yaml")).unwrap();
+		let names: Vec<String> = vec!["B1-note_worthy".into(), "B2-silent".into(), "bug".into()];
+		let suggestion = Suggestion::new(&specs, &specs.partition_label_ids(&names).0, None);
+		let mutations = plan_fix(&suggestion, &names, &specs).unwrap();
+		assert_eq!(
+			vec![
+				LabelMutation::Remove("B1-note_worthy".to_string()),
+				LabelMutation::Add("X1-topic".to_string())
+			],
+			mutations
+		);
+
+		let api = MockApi::default();
+		apply_fix(&api, &Repo::from_str("o/r").unwrap(), 42, &mutations).unwrap();
+		assert_eq!(
+			vec!["remove_label 42 B1-note_worthy", "add_labels 42 X1-topic"],
+			*api.calls.borrow()
+		);
+	}
+
+	#[test]
+	fn test_plan_fix_refuses_unknown_labels() {
+		let specs = Specs::load(&std::path::PathBuf::from("./tests/specs_priority.yaml")).unwrap();
+		let names: Vec<String> = vec!["B1-urgent".into(), "B2-silent".into(), "X1-topic".into()];
+		let suggestion = Suggestion::new(&specs, &specs.partition_label_ids(&names).0, None);
+		let err = plan_fix(&suggestion, &names, &specs).unwrap_err();
+		assert_eq!(
+			"Refusing to remove the label `B1-urgent` which is not defined in the specs",
+			err.to_string()
+		);
+	}
+
+	#[test]
+	fn test_report_non_blocking_failures() {
+		let mut specs = Specs::load(&std::path::PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		specs.rules.iter_mut().for_each(|rule| rule.severity = Severity::Warning);
+		let report = specs.run_checks(&specs.label_ids(&["B1", "X1"]).unwrap(), true, None, &None);
+		assert!(report.passed);
+
+		let body = comment_body(&report, &specs, Severity::Error);
+		assert!(body.contains("Labels check passed"));
+		assert!(body.contains("- **Note Worthy need one Prio label** (warning): "));
+
+		let api = MockApi::default();
+		report_status(&api, &Repo::from_str("o/r").unwrap(), 42, &report, Severity::Error).unwrap();
+		assert_eq!(
+			vec!["create_status abc123 true 2 non blocking rules failed"],
+			*api.calls.borrow()
+		);
+
+		// The warnings are no longer non blocking with --fail-on warning
+		assert!(comment_body(&report, &specs, Severity::Warning).contains("Labels check failed"));
+		let api = MockApi::default();
+		report_status(&api, &Repo::from_str("o/r").unwrap(), 42, &report, Severity::Warning)
+			.unwrap();
+		assert_eq!(vec!["create_status abc123 false 2 rules failed"], *api.calls.borrow());
+	}
+}
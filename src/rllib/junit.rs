@@ -0,0 +1,152 @@
+//! Conversion of a [TestReport] into a JUnit XML report that CI systems can display natively.
+
+use super::{
+	check_report::CheckReport,
+	test_report::{TestCaseReport, TestReport, TestStatus},
+};
+
+/// Escape the characters that cannot appear as is in XML text or attributes.
+fn escape(s: &str) -> String {
+	s.chars()
+		.map(|c| match c {
+			'&' => "&amp;".to_string(),
+			'<' => "&lt;".to_string(),
+			'>' => "&gt;".to_string(),
+			'"' => "&quot;".to_string(),
+			'\'' => "&apos;".to_string(),
+			'\n' => "&#10;".to_string(),
+			c => c.to_string(),
+		})
+		.collect()
+}
+
+fn pass_or_fail(b: bool) -> &'static str {
+	if b {
+		"PASS"
+	} else {
+		"FAIL"
+	}
+}
+
+/// The tips of the failed rules of a [CheckReport].
+fn failed_tips(report: &Option<CheckReport>) -> Vec<String> {
+	match report {
+		Some(report) => report.failed().map(|r| format!("{}: {}", r.name, r.text())).collect(),
+		None => Vec::new(),
+	}
+}
+
+impl TestCaseReport {
+	fn to_junit_xml(&self, classname: &str) -> String {
+		let mut xml = format!(
+			"    <testcase name=\"{}\" classname=\"{}\">\n",
+			escape(&self.name),
+			escape(classname)
+		);
+
+		xml.push_str("      <properties>\n");
+		if let Some(description) = &self.description {
+			xml.push_str(&format!(
+				"        <property name=\"description\" value=\"{}\"/>\n",
+				escape(description.trim())
+			));
+		}
+		xml.push_str(&format!(
+			"        <property name=\"labels\" value=\"{}\"/>\n",
+			escape(&self.labels.join(", "))
+		));
+		xml.push_str(&format!(
+			"        <property name=\"expected\" value=\"{}\"/>\n",
+			pass_or_fail(self.expected)
+		));
+		if let Some(actual) = self.actual {
+			xml.push_str(&format!(
+				"        <property name=\"actual\" value=\"{}\"/>\n",
+				pass_or_fail(actual)
+			));
+		}
+		xml.push_str("      </properties>\n");
+
+		match self.status {
+			TestStatus::Passed => {},
+			TestStatus::Skipped => xml.push_str("      <skipped/>\n"),
+			TestStatus::Failed if self.error.is_some() => {
+				let error = escape(self.error.as_deref().unwrap_or_default());
+				xml.push_str(&format!(
+					"      <failure message=\"{error}\" type=\"error\">{error}</failure>\n"
+				));
+			},
+			TestStatus::Failed => {
+				let tips = failed_tips(&self.report);
+				let expectation = format!(
+					"Expected the checks to {} but got {}",
+					pass_or_fail(self.expected),
+					pass_or_fail(self.actual.unwrap_or_default())
+				);
+				let message = if tips.is_empty() { expectation.clone() } else { tips.join("; ") };
+				let mut body = vec![expectation];
+				body.extend(tips);
+				xml.push_str(&format!(
+					"      <failure message=\"{}\" type=\"expectation\">{}</failure>\n",
+					escape(&message),
+					body.iter().map(|l| escape(l)).collect::<Vec<_>>().join("&#10;")
+				));
+			},
+		}
+
+		xml.push_str("    </testcase>\n");
+		xml
+	}
+}
+
+impl TestReport {
+	/// Render the report as a JUnit XML document with one `testcase` per
+	/// [TestSpec](super::tests::TestSpec).
+	pub fn to_junit_xml(&self) -> String {
+		let count = |status: TestStatus| self.tests.iter().filter(|t| t.status == status).count();
+		let tests = self.tests.len();
+		let failures = count(TestStatus::Failed);
+		let skipped = count(TestStatus::Skipped);
+		let classname = format!("{} v{}", self.specs_name, self.specs_version);
+
+		let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+		xml.push_str(&format!(
+			"<testsuites name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\">\n",
+			escape(&self.name)
+		));
+		xml.push_str(&format!(
+			"  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" skipped=\"{skipped}\">\n",
+			escape(&self.name)
+		));
+		self.tests.iter().for_each(|t| xml.push_str(&t.to_junit_xml(&classname)));
+		xml.push_str("  </testsuite>\n");
+		xml.push_str("</testsuites>\n");
+		xml
+	}
+}
+
+#[cfg(test)]
+mod test_junit {
+	use super::*;
+	use crate::rllib::{specs::Specs, tests::Tests};
+	use std::path::PathBuf;
+
+	#[test]
+	fn test_escape() {
+		assert_eq!("a &lt;b&gt; &amp; &quot;c&quot;", escape("a <b> & \"c\""));
+	}
+
+	#[test]
+	fn test_to_junit_xml() {
+		let tests = Tests::load(&PathBuf::from("./tests/tests_fail.yaml")).unwrap();
+		let specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let report = tests.run(&specs, false, false, &None);
+		let xml = report.to_junit_xml();
+
+		assert!(xml.contains("tests=\"3\" failures=\"2\" skipped=\"0\""));
+		assert!(xml.contains("<testcase name=\"Missing topics\""));
+		assert!(xml.contains("<failure message=\"Note Worthy need one Prio label: "));
+		assert!(xml.contains("Expected the checks to PASS but got FAIL"));
+		assert_eq!(3, xml.matches("</testcase>").count());
+	}
+}
@@ -13,6 +13,7 @@ impl LabelIdSet {
 	/// ```
 	/// let set = LabelIdSet::from_str("B0, B1");
 	/// ```
+	#[allow(clippy::should_implement_trait)]
 	pub fn from_str(s: &str) -> HashSet<LabelId> {
 		s.split(',')
 			.map(|s| {
@@ -1,38 +1,206 @@
 //! [LabelMatch] implementation.
 
 use super::parsed_label::LabelId;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{
+	fmt::Display,
+	hash::{Hash, Hasher},
+	str::FromStr,
+	sync::OnceLock,
+};
+
+/// The prefix of the patterns using a regex
+pub const REGEX_PREFIX: &str = "re:";
+
+/// The regex of the range patterns such as `B0..3`, compiled once since the patterns are
+/// parsed for each rule.
+static RANGE: OnceLock<Regex> = OnceLock::new();
 
 /// A type to describe one or a set of Labels
-/// either specifying it or providing a regexp matching several
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Hash)]
-pub struct LabelMatch(String);
+/// either specifying it or providing a pattern matching several:
+/// - `B1`: the label `B1` only
+/// - `B*`, `kind/*`: all the labels of the category `B` or `kind/`
+/// - `B1*`, `B1?`, `B[0-3]`, `*1`: a glob matched against the whole [LabelId]
+/// - `B0..3`, `B0..=3`: the labels of the category `B` with a code in the range
+/// - `re:^B\d$`: a regex matched against the whole [LabelId]
+/// - `!B2`: any label but the ones matched by the pattern after the `!`
+///
+/// Patterns are validated when they are parsed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct LabelMatch {
+	source: String,
+	negated: bool,
+	pattern: Pattern,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+	Exact(String),
+	Category(String),
+	Range { category: String, start: u64, end: u64 },
+	Regex(Regex),
+}
 
 impl LabelMatch {
 	/// Returns true if the passed `LabelId` matches our pattern
 	pub fn matches(&self, id: &LabelId) -> bool {
-		let pattern = &self.0;
-		if pattern.contains('*') {
-			match pattern.chars().next() {
-				Some(pat) => pat == id.letter,
-				_ => false,
-			}
+		let matches = match &self.pattern {
+			Pattern::Exact(s) => id == s.as_str(),
+			Pattern::Category(category) => category == &id.category,
+			Pattern::Range { category, start, end } =>
+				category == &id.category &&
+					id.code.parse::<u64>().is_ok_and(|code| (*start..=*end).contains(&code)),
+			Pattern::Regex(re) => re.is_match(&id.to_string()),
+		};
+		matches != self.negated
+	}
+
+	/// Returns true for the patterns starting with `!`
+	pub fn is_negated(&self) -> bool {
+		self.negated
+	}
+}
+
+/// Convert the content of a glob `[...]` class into a regex class. Everything is escaped but
+/// the ranges such as `0-3` and the leading `!` or `^` negating the class, so that the regex
+/// class syntax such as `&&` or `\d` is matched literally.
+fn glob_class(class: &str) -> String {
+	let (negation, class) = match class.strip_prefix(['!', '^']) {
+		Some(rest) => ("^", rest),
+		None => ("", class),
+	};
+	let escape = |c: char| regex::escape(&c.to_string());
+	let chars: Vec<char> = class.chars().collect();
+	let mut re = String::new();
+	let mut i = 0;
+	while i < chars.len() {
+		if i + 2 < chars.len() && chars[i + 1] == '-' {
+			re.push_str(&format!("{}-{}", escape(chars[i]), escape(chars[i + 2])));
+			i += 3;
 		} else {
-			pattern == &id.to_string()
+			re.push_str(&escape(chars[i]));
+			i += 1;
+		}
+	}
+	format!("[{negation}{re}]")
+}
+
+/// Convert a glob into an anchored regex. Only `*`, `?` and `[...]` are special.
+fn glob_to_regex(glob: &str) -> Result<Regex, String> {
+	let mut re = String::from("^");
+	let mut chars = glob.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'*' => re.push_str(".*"),
+			'?' => re.push('.'),
+			'[' => {
+				let mut class = String::new();
+				loop {
+					match chars.next() {
+						Some(']') if !class.is_empty() => break,
+						Some(c) => class.push(c),
+						None => return Err("unclosed `[`".to_string()),
+					}
+				}
+				re.push_str(&glob_class(&class));
+			},
+			c => re.push_str(&regex::escape(&c.to_string())),
 		}
 	}
+	re.push('$');
+	Regex::new(&re).map_err(|e| e.to_string())
+}
+
+fn parse_pattern(s: &str) -> Result<Pattern, String> {
+	if let Some(re) = s.strip_prefix(REGEX_PREFIX) {
+		return Regex::new(&format!("^(?:{re})$"))
+			.map(Pattern::Regex)
+			.map_err(|e| e.to_string())
+	}
+
+	let range = RANGE.get_or_init(|| Regex::new(r"^(.*?)(\d+)\.\.(=?)(\d+)$").unwrap());
+	if let Some(caps) = range.captures(s) {
+		let category = caps[1].to_string();
+		let start = caps[2].parse::<u64>().map_err(|e| e.to_string())?;
+		let end = caps[4].parse::<u64>().map_err(|e| e.to_string())?;
+		let end = match (&caps[3], end) {
+			("=", end) => end,
+			(_, 0) => return Err("the range is empty".to_string()),
+			(_, end) => end - 1,
+		};
+		if start > end {
+			return Err("the range is empty".to_string())
+		}
+		return Ok(Pattern::Range { category, start, end })
+	}
+
+	let is_glob = |s: &str| s.contains(['*', '?', '[']);
+	match s.strip_suffix('*') {
+		Some(category)
+			if !category.is_empty() &&
+				!is_glob(category) &&
+				!category.ends_with(|c: char| c.is_ascii_digit()) =>
+			Ok(Pattern::Category(category.to_string())),
+		_ if is_glob(s) => glob_to_regex(s).map(Pattern::Regex),
+		_ => Ok(Pattern::Exact(s.to_string())),
+	}
+}
+
+impl FromStr for LabelMatch {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (negated, pattern) = match s.strip_prefix('!') {
+			Some(rest) => (true, rest),
+			None => (false, s),
+		};
+		if pattern.is_empty() {
+			return Err(format!("Invalid pattern `{s}`: the pattern is empty"))
+		}
+		let pattern = parse_pattern(pattern).map_err(|e| format!("Invalid pattern `{s}`: {e}"))?;
+		Ok(Self { source: s.to_string(), negated, pattern })
+	}
+}
+
+impl TryFrom<String> for LabelMatch {
+	type Error = String;
+
+	fn try_from(s: String) -> Result<Self, Self::Error> {
+		LabelMatch::from_str(&s)
+	}
+}
+
+impl From<LabelMatch> for String {
+	fn from(m: LabelMatch) -> Self {
+		m.source
+	}
+}
+
+impl PartialEq for LabelMatch {
+	fn eq(&self, other: &Self) -> bool {
+		self.source == other.source
+	}
+}
+
+impl Eq for LabelMatch {}
+
+impl Hash for LabelMatch {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.source.hash(state)
+	}
 }
 
 impl Display for LabelMatch {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.write_fmt(format_args!("{}", self.0))
+		f.write_fmt(format_args!("{}", self.source))
 	}
 }
 
 impl From<&str> for LabelMatch {
 	fn from(s: &str) -> Self {
-		Self(s.to_string())
+		LabelMatch::from_str(s).expect("String should be a valid LabelMatch")
 	}
 }
 
@@ -45,4 +213,105 @@ mod test_label_match {
 		let m1 = LabelMatch::from("B1");
 		m1.matches(&LabelId::from("B1"));
 	}
+
+	#[test]
+	fn test_category() {
+		let m = LabelMatch::from("kind/*");
+		assert!(m.matches(&LabelId::new("kind/", "feature")));
+		assert!(!m.matches(&LabelId::new("area/", "feature")));
+		assert!(!m.matches(&LabelId::new("", "kind")));
+		assert!(LabelMatch::from("bug").matches(&LabelId::new("", "bug")));
+		assert!(!LabelMatch::from("B*").matches(&LabelId::new("", "bug")));
+	}
+
+	#[test]
+	fn test_multi_letter_category() {
+		let m = LabelMatch::from("TX*");
+		assert!(m.matches(&LabelId::from("TX12")));
+		assert!(!m.matches(&LabelId::from("T12")));
+		assert!(!LabelMatch::from("T*").matches(&LabelId::from("TX1")));
+	}
+
+	#[test]
+	fn test_glob() {
+		let m = LabelMatch::from("B1*");
+		assert!(m.matches(&LabelId::from("B1")));
+		assert!(m.matches(&LabelId::from("B12")));
+		assert!(!m.matches(&LabelId::from("B2")));
+
+		assert!(LabelMatch::from("*1").matches(&LabelId::from("X1")));
+		assert!(!LabelMatch::from("*1").matches(&LabelId::from("X2")));
+		assert!(LabelMatch::from("B1?").matches(&LabelId::from("B12")));
+		assert!(!LabelMatch::from("B1?").matches(&LabelId::from("B1")));
+		assert!(LabelMatch::from("B[0-3]").matches(&LabelId::from("B3")));
+		assert!(!LabelMatch::from("B[0-3]").matches(&LabelId::from("B4")));
+		assert!(LabelMatch::from("B[!0-3]").matches(&LabelId::from("B4")));
+		assert!(LabelMatch::from("*").matches(&LabelId::new("", "bug")));
+		assert!(LabelMatch::from("B[^0-3]").matches(&LabelId::from("B4")));
+	}
+
+	#[test]
+	fn test_glob_class_is_escaped() {
+		let m = LabelMatch::from("x[a&&b]");
+		assert!(m.matches(&LabelId::new("", "xa")));
+		assert!(m.matches(&LabelId::new("", "x&")));
+		assert!(!m.matches(&LabelId::new("", "xc")));
+
+		let m = LabelMatch::from("x[\\d]");
+		assert!(m.matches(&LabelId::new("", "xd")));
+		assert!(!m.matches(&LabelId::new("", "x1")));
+
+		let m = LabelMatch::from("x[[~]");
+		assert!(m.matches(&LabelId::new("", "x[")));
+		assert!(m.matches(&LabelId::new("", "x~")));
+		assert!(LabelMatch::from_str("x[b-a]").is_err());
+	}
+
+	#[test]
+	fn test_range() {
+		let m = LabelMatch::from("B0..3");
+		assert!(m.matches(&LabelId::from("B0")));
+		assert!(m.matches(&LabelId::from("B2")));
+		assert!(!m.matches(&LabelId::from("B3")));
+		assert!(!m.matches(&LabelId::from("X1")));
+
+		let m = LabelMatch::from("TX1..=12");
+		assert!(m.matches(&LabelId::from("TX12")));
+		assert!(!m.matches(&LabelId::from("T12")));
+	}
+
+	#[test]
+	fn test_negation() {
+		let m = LabelMatch::from("!B2");
+		assert!(m.is_negated());
+		assert!(m.matches(&LabelId::from("B1")));
+		assert!(!m.matches(&LabelId::from("B2")));
+		assert!(!LabelMatch::from("!B*").matches(&LabelId::from("B1")));
+	}
+
+	#[test]
+	fn test_regex() {
+		let m = LabelMatch::from(r"re:B\d");
+		assert!(m.matches(&LabelId::from("B1")));
+		assert!(!m.matches(&LabelId::from("B12")));
+		assert!(LabelMatch::from("re:kind/(feature|bug)").matches(&LabelId::new("kind/", "bug")));
+	}
+
+	#[test]
+	fn test_invalid() {
+		assert_eq!(
+			Err("Invalid pattern `B[0-3`: unclosed `[`".to_string()),
+			LabelMatch::from_str("B[0-3")
+		);
+		assert!(LabelMatch::from_str("re:B(")
+			.unwrap_err()
+			.starts_with("Invalid pattern `re:B(`"));
+		assert_eq!(
+			Err("Invalid pattern `B3..3`: the range is empty".to_string()),
+			LabelMatch::from_str("B3..3")
+		);
+		assert!(LabelMatch::from_str("B3..=3").is_ok());
+		assert!(LabelMatch::from_str("!").is_err());
+		assert!(serde_yaml::from_str::<LabelMatch>("B4..1").is_err());
+	}
 }
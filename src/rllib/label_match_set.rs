@@ -6,56 +6,80 @@ use serde::{Deserialize, Serialize};
 use std::{
 	collections::{hash_set::Iter, HashSet},
 	fmt::Display,
+	str::FromStr,
 };
 
 /// A [HashSet] of [LabelMatch]. It allows describing a list of
 /// [LabelId] or patterns that will expand in such a list.
 /// ## example:
 /// ```
-/// let lms = LabelMatchSet::from_str("B1, X*");
+/// use ruled_labels::{LabelId, LabelMatchSet};
+///
+/// let lms = LabelMatchSet::try_from("B1, X*").unwrap();
+/// assert!(lms.matches_label(&LabelId::from("X2")).0);
+/// assert!(!lms.matches_label(&LabelId::from("B2")).0);
 /// ```
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct LabelMatchSet(HashSet<LabelMatch>);
 
 impl LabelMatchSet {
+	/// Create a set from a list of [LabelMatch], see also [LabelMatchSet::try_from] to parse
+	/// a comma separated list of patterns.
+	pub fn new(label_matches: Vec<LabelMatch>) -> Self {
+		Self(label_matches.into_iter().collect())
+	}
+
 	#[cfg(test)]
+	#[allow(clippy::should_implement_trait)]
 	pub fn from_str(s: &str) -> Self {
-		let res: HashSet<LabelMatch> = s
-			.split(',')
-			.map(|s| {
-				let cleaned: String = s.chars().filter(|c| !c.is_whitespace()).collect();
-				LabelMatch::from(cleaned.as_str())
-			})
-			.collect();
-		LabelMatchSet::from_vec(res)
+		Self::try_from(s).expect("String should be a valid LabelMatchSet")
 	}
 
-	pub fn iter(&self) -> Iter<LabelMatch> {
+	pub fn iter(&self) -> Iter<'_, LabelMatch> {
 		self.0.iter()
 	}
 
-	#[cfg(test)]
-	fn from_vec(label_matches: HashSet<LabelMatch>) -> Self {
-		Self(label_matches)
-	}
-
 	/// Check whether the passed `LabelId` matches at least one
 	/// item in the `LabelSet`. If it matches it returns a tupple
 	/// made of the matching status as boolean as well as the list of
 	/// matching patterns.
+	///
+	/// Negated patterns such as `!B2` remove labels from the set: `[B*, !B2]` matches
+	/// all the `B` labels but `B2`. A set made only of negated patterns matches all
+	/// the labels they do not exclude.
 	pub fn matches_label(&self, id: &LabelId) -> (bool, Option<Vec<&LabelMatch>>) {
-		let matches: Vec<&LabelMatch> = self.0.iter().filter(|pat| pat.matches(id)).collect();
+		let (negated, positive): (Vec<&LabelMatch>, Vec<&LabelMatch>) =
+			self.0.iter().partition(|pat| pat.is_negated());
+		let excluded = negated.iter().any(|pat| !pat.matches(id));
+		let matches: Vec<&LabelMatch> = if positive.is_empty() {
+			negated
+		} else {
+			positive.into_iter().filter(|pat| pat.matches(id)).collect()
+		};
+		let matches = if excluded { Vec::new() } else { matches };
 		let status = !matches.is_empty();
 		let matches = if !matches.is_empty() { Some(matches) } else { None };
 		(status, matches)
 	}
 
-	/// Returns true if one of the passed `LabelId` matches items in the set.
-	pub fn matches_none(&self, _labels: &HashSet<LabelId>, _specs: &Specs) -> bool {
-		// let augmented_label_set = specs.generate_label_set(self, None);
-		// println!("augmented_label_set = {:?}", augmented_label_set);
+	/// Returns the passed `LabelId` matching items in the set, sorted.
+	pub fn matching_labels(&self, labels: &HashSet<LabelId>, specs: &Specs) -> Vec<LabelId> {
+		let ref_set = specs.generate_reference_set(self, Some(labels));
+		let mut hits: Vec<LabelId> =
+			labels.iter().filter(|&label| ref_set.contains(label)).cloned().collect();
+		hits.sort();
+		hits
+	}
 
-		unimplemented!()
+	/// Returns the number of passed `LabelId` matching items in the set.
+	pub fn count_matches(&self, labels: &HashSet<LabelId>, specs: &Specs) -> usize {
+		let ref_set = specs.generate_reference_set(self, Some(labels));
+		labels.iter().filter(|&label| ref_set.contains(label)).count()
+	}
+
+	/// Returns true if none of the passed `LabelId` matches items in the set.
+	pub fn matches_none(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		!self.matches_some(labels, specs)
 	}
 
 	/// Returns true if one of the passed `LabelId` matches items in the set.
@@ -87,7 +111,7 @@ impl LabelMatchSet {
 
 		// We now iterate the ref_set to ensure that each of the items in the set
 		// is indeed present in the `labels`.
-		ref_set.iter().map(|l| labels.contains(l)).all(|r| r)
+		ref_set.iter().all(|l| labels.contains(l))
 
 		// self.0.iter().map(|match_set| {
 		// 	let labels_under_test = match_set.filter(labels);
@@ -98,10 +122,27 @@ impl LabelMatchSet {
 		// }).all(|e| e)
 	}
 
-	#[cfg(test)]
+	/// Returns the number of [LabelMatch] in the set.
 	pub fn len(&self) -> usize {
 		self.0.len()
 	}
+
+	/// Returns true if the set does not contain any [LabelMatch].
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+/// Parse a comma separated list of patterns such as `B1, X*`.
+impl TryFrom<&str> for LabelMatchSet {
+	type Error = String;
+
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		s.split(',')
+			.map(|pattern| LabelMatch::from_str(pattern.trim()))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Self::new)
+	}
 }
 
 impl Display for LabelMatchSet {
@@ -191,11 +232,46 @@ mod test_label_set {
 		assert!(set.0.contains(&LabelMatch::from("B2")));
 	}
 
+	#[test]
+	fn test_label_set_try_from() {
+		let set = LabelMatchSet::try_from("B1, X*").unwrap();
+		assert_eq!(LabelMatchSet::new(vec![LabelMatch::from("X*"), LabelMatch::from("B1")]), set);
+		assert!(LabelMatchSet::try_from("B1, ").unwrap_err().contains("the pattern is empty"));
+	}
+
 	#[test]
 	fn test_matches() {
 		assert!(LabelMatchSet::default().matches_label(&LabelId::from("B1")).0);
 	}
 
+	#[test]
+	fn test_matches_negated() {
+		let set = LabelMatchSet::from_str("B*,!B2");
+		assert!(set.matches_label(&LabelId::from("B1")).0);
+		assert!(!set.matches_label(&LabelId::from("B2")).0);
+		assert!(!set.matches_label(&LabelId::from("X1")).0);
+
+		let set = LabelMatchSet::from_str("!B2,!X*");
+		assert!(set.matches_label(&LabelId::from("B1")).0);
+		assert!(!set.matches_label(&LabelId::from("X1")).0);
+	}
+
+	#[test]
+	fn test_count_matches() {
+		let specs_ref = &Specs::load_default().unwrap();
+		let set = LabelMatchSet::from_str("B*, X1");
+		assert_eq!(0, set.count_matches(&LabelIdSet::from_str("A1,X2"), specs_ref));
+		assert_eq!(3, set.count_matches(&LabelIdSet::from_str("B0,B9,X1,X2"), specs_ref));
+	}
+
+	#[test]
+	fn test_matches_none() {
+		let specs_ref = &Specs::load_default().unwrap();
+		assert!(LabelMatchSet::default().matches_none(&LabelIdSet::from_str("B0,X1"), specs_ref));
+		assert!(LabelMatchSet::default().matches_none(&HashSet::new(), specs_ref));
+		assert!(!LabelMatchSet::default().matches_none(&LabelIdSet::from_str("B0,B2"), specs_ref));
+	}
+
 	#[test]
 	fn test_matches_one() {
 		let specs_ref = &Specs::load_default().unwrap();
@@ -0,0 +1,222 @@
+//! The [LabelScheme] describes how the [LabelId] of a label is extracted from its name.
+
+use super::parsed_label::{CodeNumber, LabelId};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, sync::OnceLock};
+
+/// The regex of [LabelScheme::LetterNumber], compiled once since all the labels are parsed
+/// for each check.
+static LETTER_NUMBER: OnceLock<Regex> = OnceLock::new();
+
+/// The regex of a [LabelScheme::Regex]. It is compiled once, when the specs are loaded, and
+/// (de)serialized as its pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SchemeRegex(Regex);
+
+impl SchemeRegex {
+	pub fn as_str(&self) -> &str {
+		self.0.as_str()
+	}
+}
+
+impl TryFrom<&str> for SchemeRegex {
+	type Error = String;
+
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		Regex::new(s)
+			.map(Self)
+			.map_err(|e| format!("Invalid regex in the label scheme: {e}"))
+	}
+}
+
+impl TryFrom<String> for SchemeRegex {
+	type Error = String;
+
+	fn try_from(s: String) -> Result<Self, Self::Error> {
+		SchemeRegex::try_from(s.as_str())
+	}
+}
+
+impl From<SchemeRegex> for String {
+	fn from(re: SchemeRegex) -> Self {
+		re.as_str().to_string()
+	}
+}
+
+impl PartialEq for SchemeRegex {
+	fn eq(&self, other: &Self) -> bool {
+		self.as_str() == other.as_str()
+	}
+}
+
+impl Eq for SchemeRegex {}
+
+impl Display for SchemeRegex {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// How the names of the labels are turned into [LabelId]s.
+/// It is set in the specs with the `label_scheme` key, for instance:
+/// ```yaml
+/// label_scheme: !separator "/"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub enum LabelScheme {
+	/// One or more letters followed by a number, anything after the number is ignored:
+	/// `B1-note_worthy` is `B1` in the category `B` and `TX12-foo` is `TX12` in the
+	/// category `TX`. This is the default.
+	#[default]
+	#[serde(rename = "letter_number")]
+	LetterNumber,
+
+	/// The whole name is the id and there is no category: `bug`, `good first issue`.
+	#[serde(rename = "full_name")]
+	FullName,
+
+	/// The category is everything up to and including the separator:
+	/// with `/`, `kind/feature` is in the category `kind/`.
+	/// Names without the separator have no category.
+	#[serde(rename = "separator")]
+	Separator(String),
+
+	/// A regex with the `category` and `code` named groups, the id being the
+	/// concatenation of both. The `category` group is optional.
+	#[serde(rename = "regex")]
+	Regex(SchemeRegex),
+}
+
+impl LabelScheme {
+	/// Check that the scheme can be used: the separator cannot be empty and the regex needs
+	/// a `code` group. The regex itself was already compiled when the scheme was loaded.
+	pub fn validate(&self) -> Result<(), String> {
+		match self {
+			LabelScheme::Separator(separator) if separator.is_empty() =>
+				Err("The separator of the label scheme cannot be empty".to_string()),
+			LabelScheme::Regex(re) if !re.0.capture_names().any(|name| name == Some("code")) =>
+				Err(format!("The regex of the label scheme needs a `code` group: {re}")),
+			_ => Ok(()),
+		}
+	}
+
+	/// Extract the [LabelId] from the name of a label.
+	pub fn parse(&self, name: &str) -> Result<LabelId, String> {
+		let name = name.replace('\"', "");
+		let name = name.trim();
+		if name.is_empty() {
+			return Err("Invalid label: the name is empty".to_string())
+		}
+
+		match self {
+			LabelScheme::LetterNumber => {
+				let sanitized_str = name.to_uppercase();
+				let re = LETTER_NUMBER.get_or_init(|| Regex::new(r"^([A-Z]+)(\d+).*$").unwrap());
+				let caps = match re.captures(&sanitized_str) {
+					Some(caps) => caps,
+					None => return Err(format!("Err 002: Invalid label, no regexp match: {name}")),
+				};
+				let number = caps[2]
+					.parse::<CodeNumber>()
+					.map_err(|e| format!("Invalid label, {e}: {name}"))?;
+				Ok(LabelId::new(&caps[1], &number.to_string()))
+			},
+			LabelScheme::FullName => Ok(LabelId::new("", name)),
+			LabelScheme::Separator(separator) => match name.split_once(separator.as_str()) {
+				Some((category, code)) if !category.is_empty() && !code.is_empty() =>
+					Ok(LabelId::new(&format!("{category}{separator}"), code)),
+				_ => Ok(LabelId::new("", name)),
+			},
+			LabelScheme::Regex(re) => {
+				let caps =
+					re.0.captures(name)
+						.ok_or_else(|| format!("Invalid label, no regexp match: {name}"))?;
+				let category = caps.name("category").map(|m| m.as_str()).unwrap_or_default();
+				let code = caps.name("code").map(|m| m.as_str()).unwrap_or_default();
+				if code.is_empty() {
+					return Err(format!("Invalid label, empty code: {name}"))
+				}
+				Ok(LabelId::new(category, code))
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod test_label_scheme {
+	use super::*;
+
+	fn parse(scheme: &LabelScheme, name: &str) -> (String, String) {
+		let id = scheme.parse(name).unwrap();
+		(id.category, id.code)
+	}
+
+	#[test]
+	fn test_letter_number() {
+		let scheme = LabelScheme::default();
+		assert_eq!(("B".to_string(), "1".to_string()), parse(&scheme, "b01-note_worthy"));
+		assert!(scheme.parse("bug").is_err());
+	}
+
+	#[test]
+	fn test_multi_letter_category() {
+		let scheme = LabelScheme::default();
+		assert_eq!(("TX".to_string(), "12".to_string()), parse(&scheme, "TX12-foo"));
+		assert_eq!(("T".to_string(), "12".to_string()), parse(&scheme, "t12"));
+		assert!(scheme.parse("TX-12").is_err());
+	}
+
+	#[test]
+	fn test_namespaced_category() {
+		let scheme = LabelScheme::Separator("-".to_string());
+		assert_eq!(("T-".to_string(), "compiler".to_string()), parse(&scheme, "T-compiler"));
+		assert_eq!(("TX-".to_string(), "ui".to_string()), parse(&scheme, "TX-ui"));
+	}
+
+	#[test]
+	fn test_full_name() {
+		let scheme = LabelScheme::FullName;
+		assert_eq!(
+			("".to_string(), "good first issue".to_string()),
+			parse(&scheme, "good first issue")
+		);
+	}
+
+	#[test]
+	fn test_separator() {
+		let scheme = LabelScheme::Separator("/".to_string());
+		assert_eq!(("kind/".to_string(), "feature".to_string()), parse(&scheme, "kind/feature"));
+		assert_eq!(("".to_string(), "bug".to_string()), parse(&scheme, "bug"));
+		assert_eq!("kind/feature", scheme.parse("kind/feature").unwrap().to_string());
+	}
+
+	#[test]
+	fn test_regex() {
+		let regex = SchemeRegex::try_from(r"^(?P<category>[a-z]+:)(?P<code>[a-z]+)$").unwrap();
+		let scheme = LabelScheme::Regex(regex);
+		assert!(scheme.validate().is_ok());
+		assert_eq!(("area:".to_string(), "frontend".to_string()), parse(&scheme, "area:frontend"));
+		assert!(scheme.parse("Area:frontend").is_err());
+
+		assert!(SchemeRegex::try_from("(").is_err());
+		let regex = SchemeRegex::try_from("^(?P<category>.*)$").unwrap();
+		assert!(LabelScheme::Regex(regex).validate().is_err());
+	}
+
+	#[test]
+	fn test_deserialize() {
+		assert_eq!(LabelScheme::FullName, serde_yaml::from_str("full_name").unwrap());
+		assert_eq!(
+			LabelScheme::Separator("/".to_string()),
+			serde_yaml::from_str("!separator /").unwrap()
+		);
+		assert_eq!(
+			LabelScheme::Regex(SchemeRegex::try_from("^(?P<code>.+)$").unwrap()),
+			serde_yaml::from_str("!regex '^(?P<code>.+)$'").unwrap()
+		);
+		let err = serde_yaml::from_str::<LabelScheme>("!regex '('").unwrap_err();
+		assert!(err.to_string().contains("Invalid regex in the label scheme"));
+	}
+}
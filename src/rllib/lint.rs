@@ -0,0 +1,895 @@
+//! Definitions of [LintReport], [Diagnostic] and [LintReportPrinter].
+//!
+//! Besides checking that the specs can be loaded, the linter looks for mistakes that
+//! `serde` happily accepts such as unknown keys, duplicated ids or patterns that no
+//! label can ever match. Whenever possible, the [Diagnostic]s point at the offending
+//! [Location] and suggest a fix.
+
+use super::{
+	common::capitalize,
+	count::LabelCount,
+	label_match_set::LabelMatchSet,
+	parsed_label::LabelId,
+	rule::Rule,
+	rule_spec::RuleSpec,
+	source_map::{Location, SourceMap},
+	specs::{Label, Specs},
+};
+use regex::Regex;
+use serde::{
+	de::{self, Visitor},
+	forward_to_deserialize_any, Deserialize, Deserializer, Serialize,
+};
+use std::{
+	collections::HashMap,
+	fmt::Display,
+	fs,
+	path::{Path, PathBuf},
+};
+use termion::color;
+
+/// How bad a [Diagnostic] is.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+	/// The specs can be used but something looks wrong
+	Warning,
+
+	/// The specs cannot be used
+	Error,
+}
+
+impl Display for Level {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Level::Warning => f.write_str("warning"),
+			Level::Error => f.write_str("error"),
+		}
+	}
+}
+
+/// A single finding of the linter.
+#[derive(Debug, Serialize, Clone)]
+pub struct Diagnostic {
+	pub level: Level,
+	pub message: String,
+
+	/// The name of the rule the [Diagnostic] is about, if any
+	pub rule: Option<String>,
+
+	/// Where the problem is located in the spec file, if known
+	pub location: Option<Location>,
+
+	/// A suggestion to fix the problem
+	pub help: Option<String>,
+}
+
+impl Diagnostic {
+	pub fn warning(message: String) -> Self {
+		Self { level: Level::Warning, message, rule: None, location: None, help: None }
+	}
+
+	pub fn error(message: String) -> Self {
+		Self { level: Level::Error, message, rule: None, location: None, help: None }
+	}
+
+	pub fn with_rule(mut self, rule: &str) -> Self {
+		self.rule = Some(rule.to_string());
+		self
+	}
+
+	pub fn with_location(mut self, location: Option<Location>) -> Self {
+		self.location = location;
+		self
+	}
+
+	pub fn with_help(mut self, help: Option<String>) -> Self {
+		self.help = help;
+		self
+	}
+}
+
+/// The result of linting a spec file.
+#[derive(Debug, Serialize, Clone)]
+pub struct LintReport {
+	pub file: PathBuf,
+
+	/// True if the file does not contain any error
+	pub valid: bool,
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+	/// Lint the spec file located at `file_path`.
+	pub fn from_file(file_path: &PathBuf) -> Self {
+		let content = match fs::read_to_string(file_path) {
+			Ok(content) => content,
+			Err(e) => return Self::new(file_path, vec![Diagnostic::error(format!("{e}"))]),
+		};
+		let source = SourceMap::new(&content);
+
+		let mut ignored: Vec<String> = Vec::new();
+		let deserializer = serde_yaml::Deserializer::from_str(&content);
+		let specs: Specs =
+			match serde_ignored::deserialize(deserializer, |path| ignored.push(path.to_string())) {
+				Ok(specs) => specs,
+				Err(e) =>
+					return Self::new(file_path, vec![deserialization_error(&e, &content, &source)]),
+			};
+
+		let mut diagnostics: Vec<Diagnostic> =
+			ignored.iter().map(|path| unknown_key(path, &specs, &source)).collect();
+		diagnostics.extend(lint_specs(&specs, &source));
+
+		Self::new(file_path, diagnostics)
+	}
+
+	pub fn new(file_path: &Path, diagnostics: Vec<Diagnostic>) -> Self {
+		let valid = !diagnostics.iter().any(|d| d.level == Level::Error);
+		Self { file: file_path.to_path_buf(), valid, diagnostics }
+	}
+}
+
+/// The Levenshtein distance between `a` and `b`.
+fn distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+	a.chars().enumerate().for_each(|(i, ca)| {
+		let mut previous = row[0];
+		row[0] = i + 1;
+		(0..b.len()).for_each(|j| {
+			let current = row[j + 1];
+			row[j + 1] = (previous + usize::from(ca != b[j])).min(row[j] + 1).min(current + 1);
+			previous = current;
+		});
+	});
+	row[b.len()]
+}
+
+/// Returns the candidate closest to `word`, if it is close enough to be a typo, or else the
+/// shortest candidate `word` abbreviates, such as `priority` for `prio`.
+fn suggest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+	candidates
+		.iter()
+		.map(|c| (distance(word, c), *c))
+		.filter(|(d, c)| *d <= (c.len() / 3).max(1))
+		.min_by_key(|(d, _)| *d)
+		.map(|(_, c)| c)
+		.or_else(|| {
+			candidates
+				.iter()
+				.filter(|c| word.chars().count() >= 3 && c.starts_with(word))
+				.min_by_key(|c| c.len())
+				.copied()
+		})
+}
+
+/// A [Deserializer] that only records the names of the fields of the struct it is asked
+/// to deserialize. It allows suggesting the keys we know about without listing them by hand.
+struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+impl<'de, 'a> Deserializer<'de> for FieldsDeserializer<'a> {
+	type Error = de::value::Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+		Err(de::Error::custom("not a struct"))
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		fields: &'static [&'static str],
+		_visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		*self.0 = fields;
+		Err(de::Error::custom("fields recorded"))
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+		option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+		ignored_any
+	}
+}
+
+/// Returns the names of the fields of the struct `T`.
+fn fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+	let mut fields: &'static [&'static str] = &[];
+	let _ = T::deserialize(FieldsDeserializer(&mut fields));
+	fields
+}
+
+/// Returns the name of the rule containing the node at `path`, `path` looking like `rules.5.spec`.
+fn rule_name<'a>(path: &str, source: &'a SourceMap) -> Option<&'a str> {
+	let index = path.strip_prefix("rules.")?.split('.').next()?;
+	source.scalar(&format!("rules.{index}.name"))
+}
+
+/// Describe a key ignored during the deserialization. `path` looks like `rules.5.ide`.
+fn unknown_key(path: &str, specs: &Specs, source: &SourceMap) -> Diagnostic {
+	let (parent, key) = match path.rsplit_once('.') {
+		Some((parent, key)) => (Some(parent), key),
+		None => (None, path),
+	};
+	let rule = parent
+		.and_then(|parent| parent.strip_prefix("rules."))
+		.and_then(|rest| rest.split('.').next())
+		.and_then(|index| index.parse::<usize>().ok())
+		.and_then(|index| specs.rules.get(index));
+
+	let segments: Vec<&str> = parent
+		.unwrap_or_default()
+		.split('.')
+		.filter(|s| s.parse::<usize>().is_err())
+		.collect();
+	let known = match segments.as_slice() {
+		[""] => fields::<Specs>(),
+		["labels"] => fields::<Label>(),
+		["rules"] => fields::<Rule>(),
+		["rules", "spec"] => fields::<RuleSpec>(),
+		["rules", "spec", _] => fields::<LabelCount>(),
+		_ => &[],
+	};
+
+	let diagnostic = Diagnostic::warning(match (rule, parent) {
+		(Some(rule), _) => format!("Unknown key `{key}` in rule `{}` is ignored", rule.name),
+		(None, Some(parent)) => format!("Unknown key `{key}` in `{parent}` is ignored"),
+		(None, None) => format!("Unknown key `{key}` is ignored"),
+	})
+	.with_location(source.location(path))
+	.with_help(suggest(key, known).map(|s| format!("did you mean `{s}`?")));
+
+	match rule {
+		Some(rule) => diagnostic.with_rule(&rule.name),
+		None => diagnostic,
+	}
+}
+
+/// Returns the [Location] of the first occurence of `text` in `content`, starting at the line
+/// of `location`. Nested nodes are reported by `serde_yaml` at the location of their parent.
+fn find_after(content: &str, location: Location, text: &str) -> Location {
+	content
+		.lines()
+		.enumerate()
+		.skip(location.line - 1)
+		.find_map(|(index, line)| {
+			line.find(text).map(|column| Location { line: index + 1, column: column + 1 })
+		})
+		.unwrap_or(location)
+}
+
+/// Turn an error of `serde_yaml` into a [Diagnostic]. Those errors look like
+/// `rules[2].spec.require: unknown variant `one_off`, expected one of `none_of`, `one_of` at line 5
+/// column 16`.
+fn deserialization_error(
+	error: &serde_yaml::Error,
+	content: &str,
+	source: &SourceMap,
+) -> Diagnostic {
+	let message = error.to_string();
+	let message = Regex::new(r" at line \d+ column \d+$")
+		.unwrap()
+		.replace(&message, "")
+		.to_string();
+	let (path, message) = match Regex::new(r"^([\w\[\]\.]+): (.*)$").unwrap().captures(&message) {
+		Some(caps) => (caps[1].replace('[', ".").replace(']', ""), caps[2].to_string()),
+		None => (String::new(), message),
+	};
+	let mut location = error.location().map(|l| Location { line: l.line(), column: l.column() });
+	let rule = rule_name(&path, source);
+	let in_rule = rule.map(|r| format!(" in rule `{r}`")).unwrap_or_default();
+
+	let unknown = Regex::new(r"^unknown (variant|field) `([^`]*)`, expected (.*)$").unwrap();
+	let diagnostic = match unknown.captures(&message) {
+		Some(caps) => {
+			let candidates: Vec<&str> = caps[3].split('`').skip(1).step_by(2).collect();
+			// The variants of the `when`, `require` and `exclude` tokens of a spec are written
+			// as yaml tags, we point at the tag rather than at the key.
+			let is_tag = &caps[1] == "variant" && path.split('.').any(|s| s == "spec");
+			let prefix = if is_tag { "!" } else { "" };
+			let kind = if is_tag { "tag" } else { &caps[1] };
+			if is_tag {
+				location = location.map(|l| find_after(content, l, &format!("!{}", &caps[2])));
+			}
+
+			Diagnostic::error(format!("Unknown {kind} `{prefix}{}`{in_rule}", &caps[2])).with_help(
+				suggest(&caps[2], &candidates).map(|s| format!("did you mean `{prefix}{s}`?")),
+			)
+		},
+		None => {
+			// Patterns are validated while deserializing the whole set, we look for the
+			// faulty one after the location reported by serde.
+			let pattern = Regex::new(r"^Invalid pattern `([^`]*)`").unwrap();
+			if let Some(caps) = pattern.captures(&message) {
+				location = location.map(|l| find_after(content, l, &caps[1]));
+			}
+			// The regex of the label scheme is compiled while deserializing
+			if message.starts_with("Invalid regex in the label scheme") {
+				location = source.location("label_scheme").or(location);
+			}
+			Diagnostic::error(format!("{}{in_rule}", capitalize(&message)))
+		},
+	}
+	.with_location(location);
+
+	match rule {
+		Some(rule) => diagnostic.with_rule(rule),
+		None => diagnostic,
+	}
+}
+
+/// Run the semantic checks on [Specs] that could be loaded. The [SourceMap] of the spec
+/// file is used to locate the problems.
+pub fn lint_specs(specs: &Specs, source: &SourceMap) -> Vec<Diagnostic> {
+	let mut diagnostics = Vec::new();
+
+	// None of the labels can be parsed with an invalid scheme
+	if let Err(e) = specs.label_scheme().validate() {
+		diagnostics.push(Diagnostic::error(e).with_location(source.location("label_scheme")));
+		return diagnostics
+	}
+
+	let mut labels: Vec<_> = specs.labels().iter().map(|l| l.name.as_str()).collect();
+	labels.sort();
+	let mut label_ids: HashMap<LabelId, &str> = HashMap::new();
+	labels.iter().for_each(|&name| {
+		let location = source.find("labels", "name", name).and_then(|p| source.location(&p));
+		match specs.label_id(name) {
+			Ok(id) =>
+				if let Some(other) = label_ids.insert(id.clone(), name) {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"The labels `{other}` and `{name}` share the same id {id}"
+						))
+						.with_location(location),
+					)
+				},
+			Err(e) => diagnostics.push(
+				Diagnostic::error(format!("Invalid label `{name}`: {e}")).with_location(location),
+			),
+		}
+	});
+	let mut known_ids: Vec<String> = label_ids.keys().map(|id| id.to_string()).collect();
+	known_ids.sort();
+	let known_ids: Vec<&str> = known_ids.iter().map(|id| id.as_str()).collect();
+
+	let mut rule_ids: HashMap<&str, &str> = HashMap::new();
+	specs.rules.iter().enumerate().for_each(|(index, rule)| {
+		let path = format!("rules.{index}");
+
+		if let Some(id) = &rule.id {
+			if let Some(other) = rule_ids.insert(id, &rule.name) {
+				diagnostics.push(
+					Diagnostic::error(format!(
+						"The rules `{other}` and `{}` share the same id `{id}`",
+						rule.name
+					))
+					.with_rule(&rule.name)
+					.with_location(source.location(&format!("{path}.id"))),
+				)
+			}
+		}
+
+		if let Err(e) = rule.validate_message() {
+			diagnostics.push(
+				Diagnostic::error(format!("Invalid message template in rule `{}`: {e}", rule.name))
+					.with_rule(&rule.name)
+					.with_location(source.location(&format!("{path}.message"))),
+			)
+		}
+
+		if rule.spec.require.is_none() && rule.spec.exclude.is_none() {
+			diagnostics.push(
+				Diagnostic::warning(format!(
+					"The rule `{}` has neither `require` nor `exclude`, it always passes",
+					rule.name
+				))
+				.with_rule(&rule.name)
+				.with_location(source.location(&format!("{path}.spec")))
+				.with_help(Some("add a `require` or an `exclude` clause".to_string())),
+			)
+		}
+
+		// `exclude: !all_of` fails as soon as one of the labels is present, unlike `require`
+		if let Some(exclude) = &rule.spec.exclude {
+			if exclude.is_legacy_all_of(label_ids.keys()) {
+				diagnostics.push(
+					Diagnostic::warning(format!(
+						"The rule `{}` excludes `!all_of` its labels, it fails as soon as one of them is present",
+						rule.name
+					))
+					.with_rule(&rule.name)
+					.with_location(source.location(&format!("{path}.spec.exclude")))
+					.with_help(Some(
+						"use `!some_of` to keep this meaning, or `!all` of `!some_of` to fail only when all of them are present".to_string(),
+					)),
+				)
+			}
+		}
+
+		let clauses: [(&str, Vec<&LabelMatchSet>); 3] = [
+			("when", rule.spec.when.as_ref().map(|t| t.label_match_sets()).unwrap_or_default()),
+			(
+				"require",
+				rule.spec.require.as_ref().map(|t| t.label_match_sets()).unwrap_or_default(),
+			),
+			(
+				"exclude",
+				rule.spec.exclude.as_ref().map(|t| t.label_match_sets()).unwrap_or_default(),
+			),
+		];
+		clauses
+			.iter()
+			.flat_map(|(clause, sets)| sets.iter().map(move |set| (clause, set)))
+			.for_each(|(clause, set)| {
+				let clause_path = format!("{path}.spec.{clause}");
+				if set.is_empty() {
+					diagnostics.push(
+						Diagnostic::warning(format!(
+							"The `{clause}` of the rule `{}` is an empty set",
+							rule.name
+						))
+						.with_rule(&rule.name)
+						.with_location(source.location(&clause_path)),
+					);
+					return
+				}
+
+				let mut unknown: Vec<String> = set
+					.iter()
+					.filter(|pattern| !label_ids.keys().any(|id| pattern.matches(id)))
+					.map(|pattern| pattern.to_string())
+					.collect();
+				unknown.sort();
+				unknown.iter().for_each(|pattern| {
+					let location = source
+						.find(&clause_path, "", pattern)
+						.and_then(|p| source.location(&p))
+						.or_else(|| source.location(&clause_path));
+					diagnostics.push(
+						Diagnostic::warning(format!(
+						"The `{clause}` of the rule `{}` references `{pattern}` but no label matches it",
+						rule.name
+					))
+						.with_rule(&rule.name)
+						.with_location(location)
+						.with_help(
+							suggest(pattern, &known_ids).map(|s| format!("did you mean `{s}`?")),
+						),
+					)
+				});
+			});
+	});
+
+	diagnostics
+}
+
+/// The [LintReportPrinter] renders the [Diagnostic]s of a [LintReport] for humans.
+#[derive(Debug)]
+pub struct LintReportPrinter<'a> {
+	report: &'a LintReport,
+	color: bool,
+}
+
+impl<'a> LintReportPrinter<'a> {
+	pub fn new(report: &'a LintReport) -> Self {
+		Self { report, color: true }
+	}
+
+	pub fn with_color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	fn paint(&self, s: &str, c: &dyn color::Color) -> String {
+		if self.color {
+			format!("{}{s}{}", color::Fg(c), color::Fg(color::Reset))
+		} else {
+			s.to_string()
+		}
+	}
+
+	/// Print the diagnostics the way `rustc` does, with the offending line of the spec file
+	/// when we know it.
+	pub fn print(&self) {
+		let lines: Vec<String> = fs::read_to_string(&self.report.file)
+			.map(|content| content.lines().map(String::from).collect())
+			.unwrap_or_default();
+
+		self.report.diagnostics.iter().for_each(|d| {
+			let level = match d.level {
+				Level::Warning => self.paint("warning", &color::Yellow),
+				Level::Error => self.paint("error", &color::Red),
+			};
+			println!("{level}: {}", d.message);
+
+			let line = d.location.and_then(|l| lines.get(l.line - 1).map(|line| (l, line)));
+			let width = d.location.map(|l| l.line.to_string().len()).unwrap_or(1);
+			let gutter = self.paint(&format!("{:width$} |", ""), &color::Blue);
+			match (d.location, line) {
+				(Some(location), Some((_, line))) => {
+					let column = location.column - 1;
+					let len = line
+						.chars()
+						.skip(column)
+						.take_while(|c| !c.is_whitespace() && *c != ':' && *c != ',' && *c != ']')
+						.count()
+						.max(1);
+					println!(
+						"{:width$}{} {}:{location}",
+						"",
+						self.paint("-->", &color::Blue),
+						self.report.file.display()
+					);
+					println!("{gutter}");
+					println!(
+						"{} {line}",
+						self.paint(&format!("{:>width$} |", location.line), &color::Blue)
+					);
+					println!(
+						"{gutter} {:column$}{}",
+						"",
+						self.paint(&"^".repeat(len), &color::Red)
+					);
+				},
+				(Some(location), None) => println!(
+					"{:width$}{} {}:{location}",
+					"",
+					self.paint("-->", &color::Blue),
+					self.report.file.display()
+				),
+				(None, _) => {},
+			}
+			if let Some(help) = &d.help {
+				println!("{:width$} = {}: {help}", "", self.paint("help", &color::Cyan));
+			}
+			println!();
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_lint {
+	use super::*;
+
+	fn lint(yaml: &str) -> Vec<Diagnostic> {
+		let path = std::env::temp_dir().join(format!(
+			"ruled-labels-lint-{}-{}.yaml",
+			std::process::id(),
+			yaml.len()
+		));
+		fs::write(&path, yaml).unwrap();
+		let report = LintReport::from_file(&path);
+		fs::remove_file(&path).unwrap();
+		report.diagnostics
+	}
+
+	fn messages(yaml: &str) -> Vec<(Level, String)> {
+		lint(yaml).into_iter().map(|d| (d.level, d.message)).collect()
+	}
+
+	const HEADER: &str = "
+name: test
+version: 0.1.0
+description: test
+labels:
+  - name: B1-foo
+    description: foo
+    color: ffffff
+";
+
+	#[test]
+	fn test_lint_specs_ok() {
+		let report = LintReport::from_file(&PathBuf::from("./tests/specs_ok.yaml"));
+		assert!(report.valid);
+		assert!(report.diagnostics.iter().all(|d| d.level == Level::Warning));
+	}
+
+	#[test]
+	fn test_lint_unknown_keys() {
+		let messages = messages(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    ide: rule_1
+    weight: 100
+    spec:
+      require: !one_of [ B1 ]
+"
+		));
+		assert_eq!(
+			vec![
+				(Level::Warning, "Unknown key `ide` in rule `Rule 1` is ignored".to_string()),
+				(Level::Warning, "Unknown key `weight` in rule `Rule 1` is ignored".to_string()),
+			],
+			messages
+		);
+	}
+
+	#[test]
+	fn test_lint_labels() {
+		let messages = messages(
+			"
+name: test
+version: 0.1.0
+description: test
+labels:
+  - name: B1-foo
+    description: foo
+    color: ffffff
+  - name: B1-bar
+    description: bar
+    color: ffffff
+  - name: bug
+    description: bug
+    color: ffffff
+rules: []
+",
+		);
+		assert_eq!(2, messages.len());
+		assert!(messages.iter().all(|(level, _)| *level == Level::Error));
+		assert!(messages.contains(&(
+			Level::Error,
+			"The labels `B1-bar` and `B1-foo` share the same id B1".to_string()
+		)));
+		assert!(messages[1].1.starts_with("Invalid label `bug`"));
+	}
+
+	#[test]
+	fn test_lint_label_scheme() {
+		let diagnostics = lint(
+			"
+name: test
+version: 0.1.0
+description: test
+label_scheme: !regex '^(?P<category>.*)$'
+labels:
+  - name: a
+    description: a
+    color: ffffff
+  - name: b
+    description: b
+    color: ffffff
+rules: []
+",
+		);
+		assert_eq!(1, diagnostics.len());
+		assert!(diagnostics[0]
+			.message
+			.starts_with("The regex of the label scheme needs a `code` group"));
+		assert_eq!(5, diagnostics[0].location.unwrap().line);
+
+		let diagnostics = lint(
+			"
+name: test
+version: 0.1.0
+description: test
+label_scheme: !regex '('
+labels: []
+rules: []
+",
+		);
+		assert_eq!(1, diagnostics.len());
+		assert!(diagnostics[0].message.starts_with("Invalid regex in the label scheme"));
+		assert_eq!(5, diagnostics[0].location.unwrap().line);
+	}
+
+	#[test]
+	fn test_lint_large_label_number() {
+		let messages = messages(&format!(
+			"{HEADER}  - name: B99999999999999999999999-huge
+    description: huge
+    color: ffffff
+rules: []
+"
+		));
+		assert_eq!(1, messages.len());
+		assert!(messages[0].1.starts_with("Invalid label `B99999999999999999999999-huge`"));
+	}
+
+	#[test]
+	fn test_lint_rules() {
+		let messages = messages(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    id: same
+    spec:
+      require: !one_of [ B1, T* ]
+  - name: Rule 2
+    id: same
+    spec:
+      when: !one_of [ ]
+"
+		));
+		assert_eq!(
+			vec![
+				(
+					Level::Warning,
+					"The `require` of the rule `Rule 1` references `T*` but no label matches it"
+						.to_string()
+				),
+				(
+					Level::Error,
+					"The rules `Rule 1` and `Rule 2` share the same id `same`".to_string()
+				),
+				(
+					Level::Warning,
+					"The rule `Rule 2` has neither `require` nor `exclude`, it always passes"
+						.to_string()
+				),
+				(Level::Warning, "The `when` of the rule `Rule 2` is an empty set".to_string()),
+			],
+			messages
+		);
+	}
+
+	#[test]
+	fn test_suggest() {
+		assert_eq!(3, distance("kitten", "sitting"));
+		assert_eq!(0, distance("id", "id"));
+		assert_eq!(Some("one_of"), suggest("one_off", &["none_of", "one_of", "some_of", "all_of"]));
+		assert_eq!(Some("id"), suggest("ide", &["name", "id", "spec"]));
+		assert_eq!(None, suggest("priority", &["name", "id", "spec"]));
+		assert_eq!(Some("priority"), suggest("prio", &["name", "priority", "id"]));
+		assert_eq!(Some("description"), suggest("desc", &["name", "description"]));
+		assert_eq!(None, suggest("sp", &["name", "spec"]));
+	}
+
+	#[test]
+	fn test_fields() {
+		assert_eq!(&["when", "require", "exclude"], fields::<RuleSpec>());
+		assert!(fields::<Rule>().contains(&"disabled"));
+	}
+
+	#[test]
+	fn test_lint_locations_and_help() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    ide: rule_1
+    spec:
+      require: !one_of [ B1, B2 ]
+"
+		));
+		assert_eq!(2, diagnostics.len());
+
+		assert_eq!(Some(Location { line: 12, column: 5 }), diagnostics[0].location);
+		assert_eq!(Some("Rule 1".to_string()), diagnostics[0].rule);
+		assert_eq!(Some("did you mean `id`?".to_string()), diagnostics[0].help);
+
+		assert_eq!(Some(Location { line: 14, column: 30 }), diagnostics[1].location);
+		assert_eq!(Some("did you mean `B1`?".to_string()), diagnostics[1].help);
+	}
+
+	#[test]
+	fn test_lint_abbreviated_key() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    id: rule_1
+    prio: 3
+    spec:
+      require: !one_of [ B1 ]
+"
+		));
+		assert_eq!(1, diagnostics.len());
+		assert_eq!("Unknown key `prio` in rule `Rule 1` is ignored", diagnostics[0].message);
+		assert_eq!(Some("did you mean `priority`?".to_string()), diagnostics[0].help);
+	}
+
+	#[test]
+	fn test_lint_unknown_tag() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    spec:
+      require: !one_off [ B1 ]
+"
+		));
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Level::Error, diagnostics[0].level);
+		assert_eq!("Unknown tag `!one_off` in rule `Rule 1`", diagnostics[0].message);
+		assert_eq!(Some(Location { line: 13, column: 16 }), diagnostics[0].location);
+		assert_eq!(Some("did you mean `!one_of`?".to_string()), diagnostics[0].help);
+	}
+
+	#[test]
+	fn test_lint_invalid_pattern() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    spec:
+      require: !one_of
+        - B1
+        - B[0-3
+"
+		));
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Level::Error, diagnostics[0].level);
+		assert_eq!(
+			"Invalid pattern `B[0-3`: unclosed `[` in rule `Rule 1`",
+			diagnostics[0].message
+		);
+		assert_eq!(Some(Location { line: 15, column: 11 }), diagnostics[0].location);
+	}
+
+	#[test]
+	fn test_lint_invalid_message() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    message: \"Add one of {{{{ missing \"
+    spec:
+      require: !one_of [ B1 ]
+"
+		));
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Level::Error, diagnostics[0].level);
+		assert!(diagnostics[0].message.starts_with("Invalid message template in rule `Rule 1`"));
+		assert_eq!(Some(Location { line: 12, column: 5 }), diagnostics[0].location);
+	}
+
+	#[test]
+	fn test_lint_exclude_all_of() {
+		let diagnostics = lint(&format!(
+			"{HEADER}  - name: B2-bar
+    description: bar
+    color: ffffff
+rules:
+  - name: Rule 1
+    spec:
+      exclude: !all_of [ B* ]
+  - name: Rule 2
+    spec:
+      exclude: !all_of [ B1 ]
+"
+		));
+		assert_eq!(1, diagnostics.len());
+		assert_eq!(Level::Warning, diagnostics[0].level);
+		assert_eq!(Some("Rule 1".to_string()), diagnostics[0].rule);
+		assert_eq!(Some(Location { line: 15, column: 7 }), diagnostics[0].location);
+		assert_eq!(
+			Some("use `!some_of` to keep this meaning, or `!all` of `!some_of` to fail only when all of them are present".to_string()),
+			diagnostics[0].help
+		);
+	}
+
+	#[test]
+	fn test_lint_count_missing_field() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    spec:
+      require: !at_most {{ n: 1, off: [ B* ] }}
+"
+		));
+		assert_eq!(Level::Error, diagnostics[0].level);
+		assert_eq!("Missing field `of` in rule `Rule 1`", diagnostics[0].message);
+	}
+
+	#[test]
+	fn test_lint_nested_unknown_tag() {
+		let diagnostics = lint(&format!(
+			"{HEADER}
+rules:
+  - name: Rule 1
+    spec:
+      when: !all
+        - !one_of [ B1 ]
+        - !nott [ !some_of [ B* ] ]
+      require: !one_of [ B1 ]
+"
+		));
+		assert_eq!(1, diagnostics.len());
+		assert_eq!("Unknown tag `!nott` in rule `Rule 1`", diagnostics[0].message);
+		assert_eq!(Some(Location { line: 15, column: 11 }), diagnostics[0].location);
+		assert_eq!(Some("did you mean `!not`?".to_string()), diagnostics[0].help);
+	}
+}
@@ -1,17 +1,31 @@
-//! Most of the code for `ruled-labels` is located in this module.
+//! Most of the code for `ruled_labels` is located in this module.
 //! You can start looking at [Specs](specs::Specs) and [Tests](tests::Tests).
 
+pub mod analyze;
+pub mod check_report;
 pub mod common;
+pub mod coverage;
+pub mod doc;
+pub mod gen_tests;
+pub mod github;
+pub mod junit;
 pub mod label_id_set;
 pub mod label_match;
 pub mod label_match_set;
+pub mod label_scheme;
+pub mod lint;
 pub mod parsed_label;
 pub mod rule;
 pub mod rule_filter;
 pub mod rule_spec;
 pub mod rules;
+pub mod severity;
+pub mod source_map;
 pub mod specs;
+pub mod suggest;
+pub mod test_report;
 pub mod test_result;
 pub mod tests;
 pub mod token_rule;
+pub mod webhook;
 pub use token_rule::*;
@@ -1,131 +1,96 @@
 //! [ParsedLabel] and [LabelId]
 
-use regex::Regex;
+use super::label_scheme::LabelScheme;
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
-pub type CodeNumber = u8;
+pub type CodeNumber = u64;
 
-/// The [LabelId] is the initial letter + number from a Label.
-/// For instance, the [LabelId] for `B0-silent` is `B0`.
+/// The [LabelId] identifies a label. It is made of a `category` and a `code` which are extracted
+/// from the name of the label by the [LabelScheme] of the specs.
+/// For instance, with the default scheme, the [LabelId] for `B0-silent` is `B0`, made of the
+/// category `B` and the code `0`. With the `full_name` scheme, the [LabelId] for `bug` is `bug`
+/// and has no category.
 ///
 /// WARNING: Do not confuse [LabelId] with [LabelMatch](super::label_match::LabelMatch).
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
 pub struct LabelId {
-	pub letter: char,
-	pub number: CodeNumber,
+	pub category: String,
+	pub code: String,
 }
 
 impl PartialEq<LabelId> for &str {
 	fn eq(&self, l: &LabelId) -> bool {
-		let letter = match self.chars().next() {
-			Some(l) => l,
-			_ => return false,
-		};
-		let number = match self.chars().next() {
-			Some(l) => match l.to_string().parse::<CodeNumber>() {
-				Ok(n) => n,
-				_ => return false,
-			},
-			None => return false,
-		};
-		l.letter == letter && l.number == number
+		l == *self
 	}
 }
 
 impl PartialEq<str> for LabelId {
 	fn eq(&self, s: &str) -> bool {
-		let letter = match s.chars().next() {
-			Some(l) => l,
-			_ => return false,
-		};
-		let number = match s.chars().next() {
-			Some(l) => match l.to_string().parse::<CodeNumber>() {
-				Ok(n) => n,
-				_ => return false,
-			},
-			None => return false,
-		};
-		self.letter == letter && self.number == number
+		s.strip_prefix(self.category.as_str()) == Some(self.code.as_str())
 	}
 }
 
-impl From<&str> for LabelId {
-	fn from(s: &str) -> Self {
-		// TODO: switch to removes_matches once https://github.com/rust-lang/rust/issues/72826 is resolved
-		let sanitized_str = String::from(s).replace('\"', "");
-
-		LabelId::from_str(&sanitized_str).expect("String should be a valid LabelId")
+/// [LabelId]s are sorted by category, then by code. Numeric codes are compared as numbers
+/// so that `B2` comes before `B10`.
+impl Ord for LabelId {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.category.cmp(&other.category).then_with(|| {
+			match (self.code.parse::<u64>(), other.code.parse::<u64>()) {
+				(Ok(a), Ok(b)) => a.cmp(&b).then_with(|| self.code.cmp(&other.code)),
+				_ => self.code.cmp(&other.code),
+			}
+		})
 	}
 }
 
-// error[E0119]: conflicting implementations of trait `std::convert::TryFrom<&str>` for type
-// `lib::parsed_label::LabelId` impl TryFrom<&str> for LabelId {
-// 	type Error = String;
-// 	fn try_from(s: &str) -> Result<Self, Self::Error> {
-// 		LabelId::from_str(s)
-// 	}
-// }
-
-impl LabelId {
-	pub fn new(letter: char, number: CodeNumber) -> Self {
-		Self { letter, number }
+impl PartialOrd for LabelId {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
 	}
+}
 
-	/// This version, using a 1 digit code, is faster as it does not use
-	/// a regexp. See [Self::from_str] for the new version based on a regexp.
-	pub fn from_str_fast(s: &str) -> Result<Self, String> {
-		let sanitized_str = String::from(s).replace('\"', "");
-		let mut chars = sanitized_str.chars();
-		let first = chars.next();
-		let second = chars.next();
-
-		if first.is_none() || second.is_none() {
-			return Err(format!("Err 001: Invalid label: {s} ({first:?}{second:?})"))
-		}
-
-		let first = first.expect("Cannot fail").to_ascii_uppercase();
-		let second = second.expect("Cannot fail");
-
-		if !(first.is_alphabetic() && second.is_numeric()) {
-			return Err(format!("Err 002: Invalid label: {s} ({first}{second})"))
-		}
-		let second = second.to_string().parse::<CodeNumber>().expect("Cannot fail");
-		Ok(LabelId::new(first, second))
+impl From<&str> for LabelId {
+	fn from(s: &str) -> Self {
+		LabelId::from_str(s).expect("String should be a valid LabelId")
 	}
+}
 
-	/// Unlike [Self::from_str_fast], this function uses a regexp and allows supporting
-	/// 2 digits codes.
-	pub fn from_str(s: &str) -> Result<Self, String> {
-		let sanitized_str = String::from(s).replace('\"', "").to_uppercase();
-
-		let re = Regex::new(r"^([A-Z]{1})(\d+).*$").unwrap();
-		let caps = re.captures(&sanitized_str);
-
-		if caps.is_none() {
-			return Err(format!("Err 002: Invalid label, no regexp match: {s}"))
-		}
+impl TryFrom<String> for LabelId {
+	type Error = String;
 
-		let caps = caps.unwrap();
+	fn try_from(s: String) -> Result<Self, Self::Error> {
+		LabelId::from_str(&s)
+	}
+}
 
-		let first = caps.get(1).map(|m| m.as_str().chars().next().unwrap());
-		let second = caps.get(2).map(|m| m.as_str());
+impl From<LabelId> for String {
+	fn from(id: LabelId) -> Self {
+		id.to_string()
+	}
+}
 
-		if first.is_none() || second.is_none() {
-			return Err(format!("Err 001: Invalid label: {s} ({first:?}{second:?})"))
-		}
+impl LabelId {
+	/// Create a new [LabelId] from its category and code.
+	pub fn new(category: &str, code: &str) -> Self {
+		Self { category: category.to_string(), code: code.to_string() }
+	}
+}
 
-		let first = first.expect("Cannot fail").to_ascii_uppercase();
-		let second = second.expect("Cannot fail");
+impl FromStr for LabelId {
+	type Err = String;
 
-		let second = second.to_string().parse::<CodeNumber>().expect("Cannot fail");
-		Ok(LabelId::new(first, second))
+	/// Parse a [LabelId] using the default [LabelScheme]. Use [LabelScheme::parse]
+	/// or [Specs::label_id](super::specs::Specs::label_id) to use the scheme of some specs.
+	fn from_str(s: &str) -> Result<Self, String> {
+		LabelScheme::default().parse(s)
 	}
 }
 
 impl Display for LabelId {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.write_fmt(format_args!("{}{}", self.letter, self.number))
+		f.write_fmt(format_args!("{}{}", self.category, self.code))
 	}
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -140,25 +105,29 @@ impl AsRef<ParsedLabel> for ParsedLabel {
 	}
 }
 
+impl ParsedLabel {
+	/// Parse the name of a label using the passed [LabelScheme]. The description is the
+	/// full name of the label when it differs from its [LabelId].
+	pub fn parse(s: &str, scheme: &LabelScheme) -> Result<Self, String> {
+		let id = scheme.parse(s)?;
+		let name = s.replace('\"', "");
+		let name = name.trim();
+		let description = if id == *name { None } else { Some(name.to_string()) };
+		Ok(Self { id, description })
+	}
+}
+
 impl TryFrom<&str> for ParsedLabel {
 	type Error = String;
 
 	fn try_from(s: &str) -> Result<Self, Self::Error> {
-		let id = LabelId::from_str(s)?;
-		let description = s.to_string().drain(0..2).as_str().to_string();
-		let description = if description.is_empty() { None } else { Some(description) };
-		Ok(Self { id, description })
+		ParsedLabel::parse(s, &LabelScheme::default())
 	}
 }
 
 impl From<String> for ParsedLabel {
 	fn from(s: String) -> Self {
-		println!("s = {s:?}");
-		let id = LabelId::from_str(&s).unwrap();
-		let mut s = s;
-		let description = s.drain(0..2).as_str().to_string();
-		let description = if description.is_empty() { None } else { Some(description) };
-		Self { id, description }
+		ParsedLabel::try_from(s.as_str()).unwrap()
 	}
 }
 
@@ -168,36 +137,35 @@ mod test_parsed_label {
 
 	#[test]
 	fn test_parsed_label_from_str_ok() {
-		const INPUTS: &'static [&'static str] =
-			&["B0-Silent", "b0-silent", "Z9-foobar", "B0silent", "B00-Silent"];
+		const INPUTS: &[&str] = &["B0-Silent", "b0-silent", "Z9-foobar", "B0silent", "B00-Silent"];
 
 		INPUTS.iter().for_each(|&case| {
 			let label = ParsedLabel::try_from(case);
 			println!("{:?}", label);
 			assert!(label.is_ok());
 			let label = label.unwrap();
-			assert!(label.id.letter.is_uppercase());
-			assert!((0..=9).contains(&label.id.number));
+			assert!(label.id.category.chars().all(|c| c.is_uppercase()));
+			assert!((0..=9).contains(&label.id.code.parse::<CodeNumber>().unwrap()));
 		});
 	}
 
 	#[test]
 	fn test_parsed_label_str_fancy_ok() {
-		const INPUTS: &'static [&'static str] = &["B0-Foo 🧸", "\"b0-silent\""];
+		const INPUTS: &[&str] = &["B0-Foo 🧸", "\"b0-silent\""];
 
 		INPUTS.iter().for_each(|&case| {
 			let label = ParsedLabel::try_from(case);
 			println!("{:?}", label);
 			assert!(label.is_ok());
 			let label = label.unwrap();
-			assert!(label.id.letter.is_uppercase());
-			assert!((0..=9).contains(&label.id.number));
+			assert!(label.id.category.chars().all(|c| c.is_uppercase()));
+			assert!((0..=9).contains(&label.id.code.parse::<CodeNumber>().unwrap()));
 		});
 	}
 
 	#[test]
 	fn test_parsed_label_from_str_error() {
-		const INPUTS: &'static [&'static str] = &["BB-Silent", "B-silent", "99-foobar"];
+		const INPUTS: &[&str] = &["BB-Silent", "B-silent", "99-foobar"];
 		INPUTS.iter().for_each(|&case| {
 			let label = ParsedLabel::try_from(case);
 			println!("{:?}", label);
@@ -212,7 +180,7 @@ mod test_label_id {
 
 	#[test]
 	fn test_label_id_ok() {
-		const INPUTS: &'static [&'static str] =
+		const INPUTS: &[&str] =
 			&["B0-Silent", "B1-silent", "X9-foobar", "X9 -foobar", "X9 - foobar", "B0"];
 
 		INPUTS.iter().for_each(|&case| {
@@ -224,20 +192,19 @@ mod test_label_id {
 
 	#[test]
 	fn test_label_id_ok_2digits() {
-		const INPUTS: &'static [&'static str] =
-			&["B10-Silent", "B11-silent", "X09-foobar", "Z99 -foobar"];
+		const INPUTS: &[&str] = &["B10-Silent", "B11-silent", "X09-foobar", "Z99 -foobar"];
 
 		INPUTS.iter().for_each(|&case| {
 			let id = LabelId::from_str(case);
 			println!("{:?}", id);
 			assert!(id.is_ok());
-			assert!(id.unwrap().number >= 9);
+			assert!(id.unwrap().code.parse::<CodeNumber>().unwrap() >= 9);
 		});
 	}
 
 	#[test]
 	fn test_label_id_err() {
-		const INPUTS: &'static [&'static str] = &["BB-Silent", "B-silent", "99-foobar"];
+		const INPUTS: &[&str] = &["BB-Silent", "B-silent", "99-foobar"];
 
 		INPUTS.iter().for_each(|&case| {
 			let id = LabelId::from_str(case);
@@ -265,8 +232,8 @@ mod test_label_id {
 	#[test]
 	fn test_from_str() {
 		let id = LabelId::from_str("B1").unwrap();
-		assert_eq!('B', id.letter);
-		assert_eq!(1, id.number);
+		assert_eq!("B", id.category);
+		assert_eq!("1", id.code);
 	}
 
 	#[test]
@@ -282,8 +249,31 @@ mod test_label_id {
 	}
 
 	#[test]
-	#[should_panic]
 	fn test_b256() {
-		let _ = LabelId::from_str("B256");
+		assert_eq!("B256", LabelId::from_str("B256-big").unwrap().to_string());
+		assert_eq!("B300", LabelId::from_str("B0300").unwrap().to_string());
+	}
+
+	#[test]
+	fn test_number_too_large() {
+		let err = LabelId::from_str("B99999999999999999999999-huge").unwrap_err();
+		assert!(err.contains("number too large"), "{err}");
+	}
+
+	#[test]
+	fn test_serde_as_string() {
+		let id = LabelId::from_str("B12").unwrap();
+		assert_eq!("\"B12\"", serde_json::to_string(&id).unwrap());
+		assert_eq!(id, serde_json::from_str::<LabelId>("\"b12-foo\"").unwrap());
+	}
+
+	#[test]
+	fn test_ord() {
+		let mut ids: Vec<LabelId> = ["B10", "A3", "B2"].iter().map(|&s| LabelId::from(s)).collect();
+		ids.sort();
+		assert_eq!(
+			vec!["A3", "B2", "B10"],
+			ids.iter().map(|id| id.to_string()).collect::<Vec<_>>()
+		);
 	}
 }
@@ -1,19 +1,18 @@
 //! Definition of the [Rule] structure. A [Rule] defines the requirements for a set of [LabelId].
 
-use super::{
-	label_match_set::LabelMatchSet, parsed_label::LabelId, rule_spec::RuleSpec, specs::Specs,
-};
-use crate::rllib::{
-	common::set_to_string, exclude::TokenRuleExclude, require::TokenRuleRequire,
-	when::TokenRuleWhen,
-};
+use super::{parsed_label::LabelId, rule_spec::RuleSpec, severity::Severity, specs::Specs};
+use crate::rllib::common::set_to_string;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, fmt::Display};
+use tera::Tera;
 use Iterator;
 
-// fn default_priority() -> u8 {
-// 	100_u8
-// }
+/// The priority of the rules that do not set one.
+pub const DEFAULT_PRIORITY: u32 = 100;
+
+fn default_priority() -> u32 {
+	DEFAULT_PRIORITY
+}
 
 fn default_none<T>() -> Option<T> {
 	None
@@ -44,8 +43,27 @@ pub struct Rule {
 	#[serde(default = "default_none")]
 	pub tags: Option<Vec<Tag>>,
 
-	// #[serde(default = "default_priority")]
-	// pub priority: u8,
+	/// How bad a failure of this rule is, `error` by default
+	#[serde(default)]
+	pub severity: Severity,
+
+	/// The rules with a higher priority are checked and reported first
+	#[serde(default = "default_priority")]
+	pub priority: u32,
+
+	/// If this rule fails, the rules with a lower priority are not checked
+	#[serde(default = "default_false")]
+	pub stop_on_failure: bool,
+
+	/// A Tera template shown instead of the generic tip when the rule fails, see
+	/// [RuleReport::new](super::check_report::RuleReport::new) for the available variables
+	#[serde(default = "default_none")]
+	pub message: Option<String>,
+
+	/// A link to a page explaining the rule
+	#[serde(default = "default_none")]
+	pub help_url: Option<String>,
+
 	pub spec: RuleSpec,
 }
 
@@ -56,13 +74,16 @@ impl Display for Rule {
 			"{}",
 			if let Some(id) = &self.id { format!(" ({id})") } else { "".to_string() }
 		))?;
+		f.write_fmt(format_args!(
+			"{}",
+			if self.stop_on_failure { " STOP_ON_FAILURE" } else { "" }
+		))?;
 		f.write_fmt(format_args!("{}", if self.disabled { " DISABLED" } else { "" }))
 	}
 }
 
 impl Rule {
-	/// Create a new named empty rule with no specs.
-	#[cfg(test)]
+	/// Create a new named rule from a [RuleSpec].
 	pub fn new(name: &str, spec: RuleSpec) -> Self {
 		Self {
 			name: name.to_string(),
@@ -71,80 +92,30 @@ impl Rule {
 			disabled: false,
 			spec,
 			tags: None,
+			severity: Severity::default(),
+			priority: DEFAULT_PRIORITY,
+			stop_on_failure: false,
+			message: None,
+			help_url: None,
 		}
 	}
 
-	/// `label` cannot be contained in `label_set`.
-	/// This is done by calling `self.exclude_all`
-	pub fn require_none(
-		&self,
-		labels: &HashSet<LabelId>,
-		label_set: &LabelMatchSet,
-		specs: &Specs,
-	) -> bool {
-		// println!("require_none");
-		self.exclude_all(labels, label_set, specs)
-	}
-
-	// deprecated
-	// fn concat_labels(label: &LabelId, labels: &HashSet<LabelId>) -> Vec<LabelId> {
-	// 	let mut res = Vec::from(labels);
-	// 	res.push(*label);
-	// 	res
-	// }
-
-	/// Only one of the `label_set` should be part of the set `label` + `labels`
-	pub fn require_one(
-		&self,
-		labels: &HashSet<LabelId>,
-		label_set: LabelMatchSet,
-		specs: &Specs,
-	) -> bool {
-		// println!("require_one");
-		// let ids = Rule::concat_labels(labels);
-		// println!("ids = {:?}", ids);
-		// println!("labelset = {:?}", label_set);
-		label_set.matches_one(labels, specs)
-	}
-
-	pub fn require_some(
-		&self,
-		labels: &HashSet<LabelId>,
-		label_match_set: &LabelMatchSet,
-		specs: &Specs,
-	) -> bool {
-		// println!("require_some");
-		label_match_set.matches_some(labels, specs)
-	}
-
-	/// All the labels from the `LabelMatchSet` must be present
-	/// - labels: The set of LabelId to check
-	/// - label_match_set: The specs of the labels that should match, this can contain wildcards and
-	///   it is NOT expanded
-	/// - specs: reference to the full specs
-	pub fn require_all(
-		&self,
-		labels: &HashSet<LabelId>,
-		label_match_set: &LabelMatchSet,
-		specs: &Specs,
-	) -> bool {
-		// println!("require_all");
-		label_match_set.matches_all(labels, specs)
-	}
-
-	/// The passed `LabelId` should be neither be `_label` nor part of the `_label_set`.
-	pub fn exclude_all(
-		&self,
-		labels: &HashSet<LabelId>,
-		label_match_set: &LabelMatchSet,
-		specs: &Specs,
-	) -> bool {
-		let lset = specs.generate_reference_set(label_match_set, Some(labels));
-		log::debug!("lset = {:?}", lset);
-		let match_some = label_match_set.matches_some(labels, specs);
-		!match_some
+	/// Check that the `message` of the rule, if any, is a valid template.
+	pub fn validate_message(&self) -> Result<(), String> {
+		match &self.message {
+			Some(message) => Tera::default().add_raw_template(&self.name, message).map_err(|e| {
+				match std::error::Error::source(&e) {
+					Some(source) => source.to_string(),
+					None => e.to_string(),
+				}
+			}),
+			None => Ok(()),
+		}
 	}
 
+	/// Check the passed `labels` against this [Rule]. It returns `None` if the rule does not
+	/// apply (disabled, `when` condition not met or empty spec), and `Some(true)` or
+	/// `Some(false)` otherwise.
 	pub fn check(&self, labels: &HashSet<LabelId>, specs: &Specs) -> Option<bool> {
 		log::debug!("⚙️ Checking rule: {}", self);
 		log::trace!(
@@ -154,48 +125,10 @@ impl Rule {
 
 		log::debug!("when = {:?}", self.spec.when);
 		if let Some(when) = &self.spec.when {
-			match when {
-				TokenRuleWhen::None(when) =>
-					if when.matches_none(labels, specs) {
-						log::debug!(
-							"when_none {} is satisfied with {}",
-							when,
-							set_to_string(labels)
-						);
-					} else {
-						return None
-					},
-				TokenRuleWhen::One(when) =>
-					if when.matches_one(labels, specs) {
-						log::debug!(
-							"when_one {} is satisfied with {}",
-							when,
-							set_to_string(labels)
-						);
-					} else {
-						return None
-					},
-				TokenRuleWhen::Some(when) =>
-					if when.matches_some(labels, specs) {
-						log::debug!(
-							"when_some {} is satisfied with {}",
-							when,
-							set_to_string(labels)
-						);
-					} else {
-						return None
-					},
-				TokenRuleWhen::All(when) =>
-					if when.matches_all(labels, specs) {
-						log::debug!(
-							"when_all {} is satisfied with {}",
-							when,
-							set_to_string(labels)
-						);
-					} else {
-						return None
-					},
+			if !when.holds(labels, specs) {
+				return None
 			}
+			log::debug!("when {} is satisfied with {}", when, set_to_string(labels));
 		}
 
 		if self.disabled {
@@ -204,9 +137,7 @@ impl Rule {
 
 		let exclude_result = if let Some(tr) = &self.spec.exclude {
 			log::trace!("  Processing exclude rules");
-			Some(match tr {
-				TokenRuleExclude::All(ls) => self.exclude_all(labels, ls, specs),
-			})
+			Some(tr.passes(labels, specs))
 		} else {
 			log::trace!("  NO exclude rules");
 			None
@@ -214,12 +145,7 @@ impl Rule {
 
 		let require_result = if let Some(tr) = &self.spec.require {
 			log::trace!("  Processing require rules");
-			Some(match tr {
-				TokenRuleRequire::None(ls) => self.require_none(labels, ls, specs),
-				TokenRuleRequire::One(ls) => self.require_one(labels, ls.clone(), specs),
-				TokenRuleRequire::Some(ls) => self.require_some(labels, ls, specs),
-				TokenRuleRequire::All(ls) => self.require_all(labels, ls, specs),
-			})
+			Some(tr.passes(labels, specs))
 		} else {
 			log::trace!("  NO require rules");
 			None
@@ -234,7 +160,7 @@ impl Rule {
 	}
 }
 
-#[cfg(test)]
+/// An enabled rule named `Rule` with an empty [RuleSpec] and the default severity and priority.
 impl Default for Rule {
 	fn default() -> Self {
 		let spec = RuleSpec::default();
@@ -243,9 +169,13 @@ impl Default for Rule {
 			description: None,
 			id: None,
 			disabled: false,
-			// priority: 100,
+			priority: DEFAULT_PRIORITY,
+			stop_on_failure: false,
+			message: None,
+			help_url: None,
 			spec,
 			tags: None,
+			severity: Severity::default(),
 		}
 	}
 }
@@ -258,8 +188,8 @@ mod test_rules {
 	fn test_rule_default() {
 		let rule = Rule::default();
 		assert_eq!(None, rule.id);
-		assert_eq!(false, rule.disabled);
-		// assert_eq!(100, rule.priority);
+		assert!(!rule.disabled);
+		assert_eq!(100, rule.priority);
 	}
 }
 
@@ -295,29 +225,30 @@ mod test_rule {
 	#![allow(non_snake_case)]
 	use super::*;
 	use crate::rllib::{
-		exclude::TokenRuleExclude, label_id_set::LabelIdSet, label_match_set::LabelMatchSet,
-		require::TokenRuleRequire, specs::*, when::TokenRuleWhen,
+		condition::Condition, count::LabelCount, exclude::TokenRuleExclude,
+		label_id_set::LabelIdSet, label_match_set::LabelMatchSet, require::TokenRuleRequire,
+		specs::*, when::TokenRuleWhen,
 	};
 
 	#[test]
 	fn test_token_rule_deserialize() {
 		let yaml = "!one_of\n- B1\n- B2\n";
 		println!("== yaml:\n{}", yaml);
-		let rs: TokenRuleRequire = serde_yaml::from_str(&yaml).unwrap();
+		let rs: TokenRuleRequire = serde_yaml::from_str(yaml).unwrap();
 		println!("rs = {:?}", rs);
 	}
 
 	#[test]
 	fn test_token_rule_serialize() {
 		let label_set = LabelMatchSet::from_str("B1");
-		let rs: TokenRuleRequire = TokenRuleRequire::One(label_set);
+		let rs: TokenRuleRequire = TokenRuleRequire(Condition::One(label_set));
 		println!("{}", serde_yaml::to_string(&rs).unwrap());
 	}
 
 	#[test]
 	fn test_rule_serialize() {
 		let label_set = LabelMatchSet::from_str("B1");
-		let token_rule = TokenRuleRequire::One(label_set);
+		let token_rule = TokenRuleRequire(Condition::One(label_set));
 		let rs: RuleSpec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule: Rule = Rule {
 			name: "Foo".to_string(),
@@ -327,6 +258,7 @@ mod test_rule {
 			disabled: false,
 			tags: None,
 			//
+			..Default::default()
 		};
 
 		println!("{}", serde_yaml::to_string(&rule).unwrap());
@@ -342,17 +274,17 @@ spec:
   - B1
 "#;
 		println!("== yaml:\n{}", yaml);
-		let rule: Rule = serde_yaml::from_str(&yaml).unwrap();
+		let rule: Rule = serde_yaml::from_str(yaml).unwrap();
 		println!("rule = {:?}", rule);
 	}
 
 	#[test]
 	fn magic_test() {
 		let label_set = LabelMatchSet::from_str("foo");
-		let token_rule = TokenRuleRequire::One(label_set);
+		let token_rule = TokenRuleRequire(Condition::One(label_set));
 		let rs = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 
-		let s = format!("{}", serde_yaml::to_string(&rs).unwrap());
+		let s = serde_yaml::to_string(&rs).unwrap();
 		println!("{}", s);
 
 		let new_rs: RuleSpec = serde_yaml::from_str(&s).unwrap();
@@ -362,8 +294,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_none_of_true() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule =
-			TokenRuleRequire::None(LabelMatchSet::from(LabelMatchSet::from_str("B0, B1")));
+		let token_rule = TokenRuleRequire(Condition::None(LabelMatchSet::from_str("B0, B1")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "test rule".to_string(),
@@ -372,6 +303,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		// println!("rule = {:?}", rule);
@@ -383,7 +315,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_none_of_false() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleRequire::None(LabelMatchSet::from_str("B0, B1"));
+		let token_rule = TokenRuleRequire(Condition::None(LabelMatchSet::from_str("B0, B1")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "test rule".to_string(),
@@ -392,6 +324,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		// println!("rule = {:?}", rule);
@@ -403,7 +336,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_one_of_true() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleRequire::One(LabelMatchSet::from_str("B0, B1"));
+		let token_rule = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("B0, B1")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "test rule".to_string(),
@@ -412,6 +345,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		// println!("rule = {:?}", rule);
@@ -423,7 +357,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_one_of_false() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleRequire::One(LabelMatchSet::from_str("B0,B1"));
+		let token_rule = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("B0,B1")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "test rule".to_string(),
@@ -432,6 +366,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		// println!("rule = {:?}", rule);
@@ -443,7 +378,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_some_of() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleRequire::Some(LabelMatchSet::from_str("B0,B1"));
+		let token_rule = TokenRuleRequire(Condition::Some(LabelMatchSet::from_str("B0,B1")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "test rule".to_string(),
@@ -453,6 +388,7 @@ spec:
 			spec,
 			tags: None,
 			//
+			..Default::default()
 		};
 
 		// println!("rule = {:?}", rule);
@@ -464,7 +400,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_all_of() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleRequire::All(LabelMatchSet::from_str("X1,X2,X3"));
+		let token_rule = TokenRuleRequire(Condition::All(LabelMatchSet::from_str("X1,X2,X3")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule::new("test rule", spec);
 
@@ -476,7 +412,7 @@ spec:
 	#[test]
 	fn test_rule_check_require_all_of_with_star() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleRequire::All(LabelMatchSet::from_str("X*"));
+		let token_rule = TokenRuleRequire(Condition::All(LabelMatchSet::from_str("X*")));
 		let spec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule::new("test rule", spec);
 		assert_eq!(Some(true), rule.check(&LabelIdSet::from_str("X1,X2,X3"), specs));
@@ -485,10 +421,30 @@ spec:
 		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("X1,X2"), specs));
 	}
 
+	#[test]
+	fn test_rule_check_exclude_variants() {
+		let specs = &Specs::load_test_default().unwrap();
+		let set = || LabelMatchSet::from_str("B0, B1");
+		let cases = [
+			(TokenRuleExclude(Condition::None(set())), [false, true, true]),
+			(TokenRuleExclude(Condition::One(set())), [true, false, true]),
+			(TokenRuleExclude(Condition::Some(set())), [true, false, false]),
+			(TokenRuleExclude(Condition::All(set())), [true, false, false]),
+		];
+		let labels = ["X1", "B0, X1", "B0, B1"].map(LabelIdSet::from_str);
+
+		cases.into_iter().for_each(|(token, expected)| {
+			let rule = Rule::new("exclude", RuleSpec::new(None, None, Some(token)));
+			labels.iter().zip(expected).for_each(|(labels, expected)| {
+				assert_eq!(Some(expected), rule.check(labels, specs), "{}", rule.spec.to_user_tip())
+			});
+		});
+	}
+
 	#[test]
 	fn test_rule_check_exclude_all_of_false() {
 		let specs = &Specs::load_test_default().unwrap();
-		let token_rule = TokenRuleExclude::All(LabelMatchSet::from_str("B0, B1"));
+		let token_rule = TokenRuleExclude(Condition::All(LabelMatchSet::from_str("B0, B1")));
 		let spec = RuleSpec { exclude: Some(token_rule), require: None, when: None };
 		let rule = Rule {
 			name: "test rule".to_string(),
@@ -497,6 +453,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		let res = rule.check(&LabelIdSet::from_str("B0, B1, B2"), specs);
@@ -507,8 +464,8 @@ spec:
 	#[test]
 	fn test_rule_check_when_B1_require_A1() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_one_b1 = TokenRuleWhen::One(LabelMatchSet::from_str("B1"));
-		let require_one_a1 = TokenRuleRequire::One(LabelMatchSet::from_str("A1"));
+		let when_one_b1 = TokenRuleWhen(Condition::One(LabelMatchSet::from_str("B1")));
+		let require_one_a1 = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("A1")));
 		let spec =
 			RuleSpec { when: Some(when_one_b1), require: Some(require_one_a1), exclude: None };
 		let rule = Rule {
@@ -518,6 +475,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("B0, B1, B2"), specs));
@@ -529,8 +487,8 @@ spec:
 	#[test]
 	fn test_rule_check_when_all_of_B_require_one_A() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_all_b = TokenRuleWhen::All(LabelMatchSet::from_str("B*"));
-		let require_one_a = TokenRuleRequire::One(LabelMatchSet::from_str("A*"));
+		let when_all_b = TokenRuleWhen(Condition::All(LabelMatchSet::from_str("B*")));
+		let require_one_a = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("A*")));
 		let spec = RuleSpec { when: Some(when_all_b), require: Some(require_one_a), exclude: None };
 
 		let rule = Rule {
@@ -540,6 +498,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("B0, B1, B2"), specs));
@@ -551,8 +510,8 @@ spec:
 	#[test]
 	fn test_rule_check_when_all_of_B_require_some_As() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_all_b = TokenRuleWhen::All(LabelMatchSet::from_str("B*"));
-		let require_some_a = TokenRuleRequire::Some(LabelMatchSet::from_str("A*"));
+		let when_all_b = TokenRuleWhen(Condition::All(LabelMatchSet::from_str("B*")));
+		let require_some_a = TokenRuleRequire(Condition::Some(LabelMatchSet::from_str("A*")));
 		let spec =
 			RuleSpec { when: Some(when_all_b), require: Some(require_some_a), exclude: None };
 
@@ -563,6 +522,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("B0, B1, B2"), specs));
@@ -574,8 +534,8 @@ spec:
 	#[test]
 	fn test_rule_check_when_one_of_B_require_A1() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_one_b = TokenRuleWhen::One(LabelMatchSet::from_str("B*"));
-		let require_one_a1 = TokenRuleRequire::One(LabelMatchSet::from_str("A1"));
+		let when_one_b = TokenRuleWhen(Condition::One(LabelMatchSet::from_str("B*")));
+		let require_one_a1 = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("A1")));
 		let spec =
 			RuleSpec { when: Some(when_one_b), require: Some(require_one_a1), exclude: None };
 
@@ -586,6 +546,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(true), rule.check(&LabelIdSet::from_str("B0, A1"), specs));
@@ -597,8 +558,8 @@ spec:
 	#[test]
 	fn test_rule_check_when_one_of_B_require_some_As() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_one_b = TokenRuleWhen::One(LabelMatchSet::from_str("B*"));
-		let require_some_a = TokenRuleRequire::Some(LabelMatchSet::from_str("A*"));
+		let when_one_b = TokenRuleWhen(Condition::One(LabelMatchSet::from_str("B*")));
+		let require_some_a = TokenRuleRequire(Condition::Some(LabelMatchSet::from_str("A*")));
 		let spec =
 			RuleSpec { when: Some(when_one_b), require: Some(require_some_a), exclude: None };
 
@@ -609,6 +570,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(true), rule.check(&LabelIdSet::from_str("B0, A1"), specs));
@@ -620,8 +582,8 @@ spec:
 	#[test]
 	fn test_rule_check_when_some_of_B_require_some_A() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_some_b = TokenRuleWhen::Some(LabelMatchSet::from_str("B*"));
-		let require_some_a = TokenRuleRequire::Some(LabelMatchSet::from_str("A*"));
+		let when_some_b = TokenRuleWhen(Condition::Some(LabelMatchSet::from_str("B*")));
+		let require_some_a = TokenRuleRequire(Condition::Some(LabelMatchSet::from_str("A*")));
 		let spec =
 			RuleSpec { when: Some(when_some_b), require: Some(require_some_a), exclude: None };
 
@@ -632,6 +594,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("B0, T8"), specs));
@@ -646,9 +609,9 @@ spec:
 	#[test]
 	fn test_rule_check_when_some_of_B_require_some_A_and_exclude_all_X() {
 		let specs = &Specs::load_test_default().unwrap();
-		let when_some_b = TokenRuleWhen::Some(LabelMatchSet::from_str("B*"));
-		let require_some_a = TokenRuleRequire::Some(LabelMatchSet::from_str("A*"));
-		let exclude_all_x = TokenRuleExclude::All(LabelMatchSet::from_str("X*"));
+		let when_some_b = TokenRuleWhen(Condition::Some(LabelMatchSet::from_str("B*")));
+		let require_some_a = TokenRuleRequire(Condition::Some(LabelMatchSet::from_str("A*")));
+		let exclude_all_x = TokenRuleExclude(Condition::All(LabelMatchSet::from_str("X*")));
 
 		let spec = RuleSpec {
 			when: Some(when_some_b),
@@ -663,6 +626,7 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		assert_eq!(Some(true), rule.check(&LabelIdSet::from_str("B0, A1"), specs));
@@ -676,8 +640,8 @@ spec:
 	#[test]
 	fn test_rule_check_require_one_p_and_no_x() {
 		let mut specs = Specs::load_test_default().unwrap();
-		let require_one_p = TokenRuleRequire::One(LabelMatchSet::from_str("P*"));
-		let exclude_all_x = TokenRuleExclude::All(LabelMatchSet::from_str("X*"));
+		let require_one_p = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("P*")));
+		let exclude_all_x = TokenRuleExclude(Condition::All(LabelMatchSet::from_str("X*")));
 
 		let spec =
 			RuleSpec { when: None, require: Some(require_one_p), exclude: Some(exclude_all_x) };
@@ -689,10 +653,209 @@ spec:
 			disabled: false,
 			spec,
 			tags: None,
+			..Default::default()
 		};
 
 		specs.rules = vec![rule.clone()];
 
 		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("A1, B1"), &specs));
 	}
+
+	/// Checks every `when` variant against every `require` and `exclude` variant.
+	/// The `when` set is `X1, X2` and the other sets are `B0, B1`.
+	#[test]
+	fn test_rule_check_when_matrix() {
+		let specs = &Specs::load_test_default().unwrap();
+		let labels = ["P1", "X1,B0", "X1,X2,B0,B1"].map(LabelIdSet::from_str);
+		let set = || LabelMatchSet::from_str("B0, B1");
+
+		let whens: [(TokenRuleWhen, [bool; 3]); 4] = [
+			(
+				TokenRuleWhen(Condition::None(LabelMatchSet::from_str("X1, X2"))),
+				[true, false, false],
+			),
+			(
+				TokenRuleWhen(Condition::One(LabelMatchSet::from_str("X1, X2"))),
+				[false, true, false],
+			),
+			(
+				TokenRuleWhen(Condition::Some(LabelMatchSet::from_str("X1, X2"))),
+				[false, true, true],
+			),
+			(
+				TokenRuleWhen(Condition::All(LabelMatchSet::from_str("X1, X2"))),
+				[false, false, true],
+			),
+		];
+		let specs_to_check: [(RuleSpec, [bool; 3]); 8] = [
+			(
+				RuleSpec::new(None, Some(TokenRuleRequire(Condition::None(set()))), None),
+				[true, false, false],
+			),
+			(
+				RuleSpec::new(None, Some(TokenRuleRequire(Condition::One(set()))), None),
+				[false, true, false],
+			),
+			(
+				RuleSpec::new(None, Some(TokenRuleRequire(Condition::Some(set()))), None),
+				[false, true, true],
+			),
+			(
+				RuleSpec::new(None, Some(TokenRuleRequire(Condition::All(set()))), None),
+				[false, false, true],
+			),
+			(
+				RuleSpec::new(None, None, Some(TokenRuleExclude(Condition::None(set())))),
+				[false, true, true],
+			),
+			(
+				RuleSpec::new(None, None, Some(TokenRuleExclude(Condition::One(set())))),
+				[true, false, true],
+			),
+			(
+				RuleSpec::new(None, None, Some(TokenRuleExclude(Condition::Some(set())))),
+				[true, false, false],
+			),
+			(
+				RuleSpec::new(None, None, Some(TokenRuleExclude(Condition::All(set())))),
+				[true, false, false],
+			),
+		];
+
+		whens.iter().for_each(|(when, applies)| {
+			specs_to_check.iter().for_each(|(spec, passes)| {
+				let spec = RuleSpec { when: Some(when.clone()), ..spec.clone() };
+				let rule = Rule::new("matrix", spec);
+				labels.iter().enumerate().for_each(|(index, labels)| {
+					let expected = if applies[index] { Some(passes[index]) } else { None };
+					assert_eq!(
+						expected,
+						rule.check(labels, specs),
+						"{} with {}",
+						rule.spec.to_user_tip(),
+						set_to_string(labels)
+					);
+				});
+			});
+		});
+	}
+
+	#[test]
+	fn test_rule_check_counts() {
+		let specs = &Specs::load_test_default().unwrap();
+		let x = || LabelMatchSet::from_str("X*");
+		let labels = ["B1", "X1, X2", "X1, X2, X3"].map(LabelIdSet::from_str);
+
+		let cases = [
+			(
+				RuleSpec::new(
+					None,
+					Some(TokenRuleRequire(Condition::AtLeast(LabelCount::new(2, x())))),
+					None,
+				),
+				[false, true, true],
+			),
+			(
+				RuleSpec::new(
+					None,
+					Some(TokenRuleRequire(Condition::AtMost(LabelCount::new(2, x())))),
+					None,
+				),
+				[true, true, false],
+			),
+			(
+				RuleSpec::new(
+					None,
+					Some(TokenRuleRequire(Condition::Exactly(LabelCount::new(2, x())))),
+					None,
+				),
+				[false, true, false],
+			),
+			(
+				RuleSpec::new(
+					None,
+					None,
+					Some(TokenRuleExclude(Condition::AtLeast(LabelCount::new(2, x())))),
+				),
+				[true, false, false],
+			),
+			(
+				RuleSpec::new(
+					None,
+					None,
+					Some(TokenRuleExclude(Condition::AtMost(LabelCount::new(2, x())))),
+				),
+				[false, false, true],
+			),
+			(
+				RuleSpec::new(
+					None,
+					None,
+					Some(TokenRuleExclude(Condition::Exactly(LabelCount::new(2, x())))),
+				),
+				[true, false, true],
+			),
+		];
+		cases.into_iter().for_each(|(spec, expected)| {
+			let rule = Rule::new("count", spec);
+			labels.iter().zip(expected).for_each(|(labels, expected)| {
+				assert_eq!(Some(expected), rule.check(labels, specs), "{}", rule.spec.to_user_tip())
+			});
+		});
+
+		let when = TokenRuleWhen(Condition::AtLeast(LabelCount::new(3, x())));
+		let require = TokenRuleRequire(Condition::One(LabelMatchSet::from_str("B*")));
+		let rule = Rule::new("when count", RuleSpec::new(Some(when), Some(require), None));
+		assert_eq!(None, rule.check(&labels[1], specs));
+		assert_eq!(Some(false), rule.check(&labels[2], specs));
+	}
+
+	#[test]
+	fn test_count_deserialize_and_tip() {
+		let spec: RuleSpec = serde_yaml::from_str(
+			"when: !exactly { n: 1, of: [ B* ] }\nrequire: !at_most { n: 2, of: [ X* ] }\nexclude: !at_least { n: 1, of: [ J* ] }",
+		)
+		.unwrap();
+
+		assert_eq!(
+			"Since you have exactly 1 of the B* label(s), you need to include at most 2 of the X* label(s) and you cannot have 1 or more of the J* label(s)",
+			spec.to_user_tip()
+		);
+	}
+
+	#[test]
+	fn test_rule_check_combinators() {
+		let specs = &Specs::load_test_default().unwrap();
+		let rule: Rule = serde_yaml::from_str(
+			"name: B1 without J needs P
+spec:
+  when: !all
+    - !one_of [ B1 ]
+    - !not [ !some_of [ J* ] ]
+  require: !one_of [ P* ]
+",
+		)
+		.unwrap();
+
+		assert_eq!(Some(true), rule.check(&LabelIdSet::from_str("B1, P1"), specs));
+		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("B1"), specs));
+		assert_eq!(None, rule.check(&LabelIdSet::from_str("B1, J1"), specs));
+		assert_eq!(None, rule.check(&LabelIdSet::from_str("B2"), specs));
+		assert_eq!(
+			"Since you have one of the B1 label(s) and not some of the J* label(s), you need to include one of the P* label(s)",
+			rule.spec.to_user_tip()
+		);
+
+		let rule: Rule = serde_yaml::from_str(
+			"name: No junk
+spec:
+  require: !any [ !one_of [ B1 ], !all_of [ X1, X2 ] ]
+  exclude: !all [ !some_of [ J* ], !some_of [ D* ] ]
+",
+		)
+		.unwrap();
+		assert_eq!(Some(true), rule.check(&LabelIdSet::from_str("X1, X2, J1"), specs));
+		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("X1, X2, J1, D1"), specs));
+		assert_eq!(Some(false), rule.check(&LabelIdSet::from_str("X1"), specs));
+	}
 }
@@ -1,8 +1,8 @@
 use super::rule::RuleId;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// A [RuleFilter] allows a test to specify the list of rules that should be ran
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RuleFilter {
 	pub id: Vec<RuleId>,
 }
@@ -1,5 +1,6 @@
 use super::{
-	common::capitalize, exclude::TokenRuleExclude, require::TokenRuleRequire, when::TokenRuleWhen,
+	common::capitalize, exclude::TokenRuleExclude, label_match_set::LabelMatchSet,
+	require::TokenRuleRequire, when::TokenRuleWhen,
 };
 use serde::{Deserialize, Serialize};
 
@@ -7,7 +8,7 @@ use serde::{Deserialize, Serialize};
 /// - **when** the rule should be applied
 /// - what [LabelMatch](super::label_match::LabelMatch) are **require**d
 /// - what [LabelMatch](super::label_match::LabelMatch) are **exclude**d
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct RuleSpec {
 	pub when: Option<TokenRuleWhen>,
 	pub require: Option<TokenRuleRequire>,
@@ -15,7 +16,7 @@ pub struct RuleSpec {
 }
 
 impl RuleSpec {
-	#[cfg(test)]
+	/// Create a new [RuleSpec] from its optional `when`, `require` and `exclude` tokens.
 	pub fn new(
 		when: Option<TokenRuleWhen>,
 		require: Option<TokenRuleRequire>,
@@ -24,6 +25,19 @@ impl RuleSpec {
 		Self { when, require, exclude }
 	}
 
+	/// Returns the [LabelMatchSet] of the `when`, `require` and `exclude` tokens.
+	pub fn label_match_sets(&self) -> Vec<&LabelMatchSet> {
+		[
+			self.when.as_ref().map(|t| t.label_match_sets()),
+			self.require.as_ref().map(|t| t.label_match_sets()),
+			self.exclude.as_ref().map(|t| t.label_match_sets()),
+		]
+		.into_iter()
+		.flatten()
+		.flatten()
+		.collect()
+	}
+
 	/// This function converts a [RuleSpec] into a user tip that
 	/// can be shown if a rule check fails. The goal is **not** to
 	/// tell the user what is wrong but tell the user how to fix it.
@@ -47,23 +61,16 @@ impl RuleSpec {
 	}
 }
 
-#[cfg(test)]
-impl Default for RuleSpec {
-	fn default() -> Self {
-		Self { require: None, exclude: None, when: None }
-	}
-}
-
 #[cfg(test)]
 mod test_rule_spec {
 	use super::*;
-	use crate::rllib::label_match_set::LabelMatchSet;
+	use crate::rllib::condition::Condition;
 
 	#[test]
 	fn test_rule_spec_serialize() {
 		let label_set = LabelMatchSet::from_str("B1");
 
-		let token_rule = TokenRuleRequire::One(label_set);
+		let token_rule = TokenRuleRequire(Condition::One(label_set));
 		let rs: RuleSpec = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 
 		println!("{}", serde_yaml::to_string(&rs).unwrap());
@@ -73,16 +80,16 @@ mod test_rule_spec {
 	fn test_deserialize_rule_spec() {
 		let yaml = "require: !one_of [ B* ]";
 		println!("== yaml:\n{}", yaml);
-		let rs: RuleSpec = serde_yaml::from_str(&yaml).unwrap();
+		let rs: RuleSpec = serde_yaml::from_str(yaml).unwrap();
 		println!("rs = {:?}", rs);
 	}
 
 	#[test]
 	fn test_rule_spec_to_tip() {
 		// let specs = &Specs::load_test_default().unwrap();
-		let when = TokenRuleWhen::All(LabelMatchSet::from_str("B*"));
-		let require = TokenRuleRequire::Some(LabelMatchSet::from_str("A*"));
-		let exlude = TokenRuleExclude::All(LabelMatchSet::from_str("B*"));
+		let when = TokenRuleWhen(Condition::All(LabelMatchSet::from_str("B*")));
+		let require = TokenRuleRequire(Condition::Some(LabelMatchSet::from_str("A*")));
+		let exlude = TokenRuleExclude(Condition::All(LabelMatchSet::from_str("B*")));
 
 		let tip = RuleSpec::new(None, None, None).to_user_tip();
 		println!("tip: {}", tip);
@@ -0,0 +1,62 @@
+//! Definition of the [Severity] of a [Rule](super::rule::Rule).
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// How bad it is for a [Rule](super::rule::Rule) to fail. Only the failures of `error` rules
+/// make a check fail by default, the other ones are reported but do not fail the check.
+/// The variants are ordered from the least to the most severe.
+#[derive(
+	Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+	/// The failure is only informative
+	Info,
+
+	/// The failure should be looked at but is not blocking
+	Warning,
+
+	/// The failure is blocking, this is the default
+	#[default]
+	Error,
+}
+
+impl Severity {
+	/// The exit code of `check` when the most severe of the failed rules has this severity.
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Severity::Error => 1,
+			Severity::Warning => 2,
+			Severity::Info => 3,
+		}
+	}
+}
+
+impl Display for Severity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Severity::Info => f.write_str("info"),
+			Severity::Warning => f.write_str("warning"),
+			Severity::Error => f.write_str("error"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test_severity {
+	use super::*;
+
+	#[test]
+	fn test_order() {
+		assert!(Severity::Info < Severity::Warning);
+		assert!(Severity::Warning < Severity::Error);
+		assert_eq!(Severity::Error, Severity::default());
+	}
+
+	#[test]
+	fn test_deserialize() {
+		assert_eq!(Severity::Warning, serde_yaml::from_str("warning").unwrap());
+		assert!(serde_yaml::from_str::<Severity>("fatal").is_err());
+	}
+}
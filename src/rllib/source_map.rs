@@ -0,0 +1,174 @@
+//! The [SourceMap] remembers where each node of a yaml document is located so we can point
+//! the users at the right place in their spec files.
+
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Display};
+use yaml_rust2::{
+	parser::{Event, MarkedEventReceiver, Parser},
+	scanner::Marker,
+};
+
+/// A position in a file, both `line` and `column` start at 1.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+	pub line: usize,
+	pub column: usize,
+}
+
+impl Display for Location {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_fmt(format_args!("{}:{}", self.line, self.column))
+	}
+}
+
+/// Maps the path of the nodes of a yaml document to their [Location].
+/// Paths use the same format as `serde_ignored`: `rules.5.spec.require.0`, the path of
+/// the root node being empty. For the values of a mapping, the location is the one of the key.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+	locations: HashMap<String, Location>,
+	scalars: HashMap<String, String>,
+}
+
+enum Frame {
+	Mapping { path: String, key: Option<String> },
+	Sequence { path: String, index: usize },
+}
+
+#[derive(Default)]
+struct Receiver {
+	map: SourceMap,
+	stack: Vec<Frame>,
+}
+
+fn join(parent: &str, child: &str) -> String {
+	if parent.is_empty() {
+		child.to_string()
+	} else {
+		format!("{parent}.{child}")
+	}
+}
+
+impl MarkedEventReceiver for Receiver {
+	fn on_event(&mut self, event: Event, mark: Marker) {
+		let location = Location { line: mark.line(), column: mark.col() + 1 };
+
+		let path = match (&event, self.stack.last_mut()) {
+			(Event::MappingEnd | Event::SequenceEnd, _) => {
+				self.stack.pop();
+				return
+			},
+			(
+				Event::Scalar(..) |
+				Event::MappingStart(..) |
+				Event::SequenceStart(..) |
+				Event::Alias(_),
+				frame,
+			) => match frame {
+				None => String::new(),
+				Some(Frame::Mapping { path, key }) => match key.take() {
+					Some(key) => join(path, &key),
+					None => {
+						// This node is a key, the location of the value is the one of its key and
+						// the location of the mapping is the one of its first key.
+						if let Event::Scalar(key_name, ..) = &event {
+							self.map.locations.entry(path.clone()).or_insert(location);
+							self.map.locations.insert(join(path, key_name), location);
+							*key = Some(key_name.clone());
+						}
+						return
+					},
+				},
+				Some(Frame::Sequence { path, index }) => {
+					*index += 1;
+					join(path, &(*index - 1).to_string())
+				},
+			},
+			_ => return,
+		};
+
+		if !matches!(event, Event::MappingStart(..)) {
+			self.map.locations.entry(path.clone()).or_insert(location);
+		}
+		match event {
+			Event::Scalar(value, ..) => {
+				self.map.scalars.insert(path, value);
+			},
+			Event::MappingStart(..) => self.stack.push(Frame::Mapping { path, key: None }),
+			Event::SequenceStart(..) => self.stack.push(Frame::Sequence { path, index: 0 }),
+			_ => {},
+		}
+	}
+}
+
+impl SourceMap {
+	/// Build the [SourceMap] of a yaml document. If the document is not valid yaml,
+	/// only the nodes located before the error are known.
+	pub fn new(yaml: &str) -> Self {
+		let mut receiver = Receiver::default();
+		if let Err(e) = Parser::new_from_str(yaml).load(&mut receiver, false) {
+			log::debug!("Partial source map: {e}");
+		}
+		receiver.map
+	}
+
+	/// Returns the [Location] of the node at `path`.
+	pub fn location(&self, path: &str) -> Option<Location> {
+		self.locations.get(path).copied()
+	}
+
+	/// Returns the value of the scalar at `path`.
+	pub fn scalar(&self, path: &str) -> Option<&str> {
+		self.scalars.get(path).map(|s| s.as_str())
+	}
+
+	/// Returns the path of the first item of the sequence at `path` for which the scalar
+	/// at `field` equals `value`. Use an empty `field` to compare the items themselves.
+	pub fn find(&self, path: &str, field: &str, value: &str) -> Option<String> {
+		(0..)
+			.map(|index| join(path, &index.to_string()))
+			.take_while(|item| self.locations.contains_key(item))
+			.find(|item| {
+				let target = if field.is_empty() { item.clone() } else { join(item, field) };
+				self.scalar(&target) == Some(value)
+			})
+	}
+}
+
+#[cfg(test)]
+mod test_source_map {
+	use super::*;
+
+	const YAML: &str = "name: test
+rules:
+  - name: Rule 1
+    spec:
+      require: !one_of [ B1, X* ]
+  - name: Rule 2
+";
+
+	#[test]
+	fn test_locations() {
+		let map = SourceMap::new(YAML);
+
+		assert_eq!(Some(Location { line: 1, column: 1 }), map.location("name"));
+		assert_eq!(Some(Location { line: 2, column: 1 }), map.location("rules"));
+		assert_eq!(Some(Location { line: 3, column: 5 }), map.location("rules.0"));
+		assert_eq!(Some(Location { line: 5, column: 7 }), map.location("rules.0.spec.require"));
+		assert_eq!(Some(Location { line: 5, column: 30 }), map.location("rules.0.spec.require.1"));
+		assert_eq!(Some("Rule 2"), map.scalar("rules.1.name"));
+		assert_eq!(None, map.location("rules.2"));
+	}
+
+	#[test]
+	fn test_find() {
+		let map = SourceMap::new(YAML);
+
+		assert_eq!(Some("rules.1".to_string()), map.find("rules", "name", "Rule 2"));
+		assert_eq!(
+			Some("rules.0.spec.require.1".to_string()),
+			map.find("rules.0.spec.require", "", "X*")
+		);
+		assert_eq!(None, map.find("rules", "name", "Rule 3"));
+	}
+}
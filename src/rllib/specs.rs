@@ -1,8 +1,8 @@
 //! Definition for [Specs] and [Label]
 
 use crate::rllib::{
+	check_report::{CheckReport, RuleOutcome, RuleReport},
 	common::set_to_string,
-	test_result::{ResultPrinter, TestResult},
 };
 use anyhow::{Context, Result};
 
@@ -13,13 +13,20 @@ pub const DEFAULT_SPEC_FILE: &str = "specs.yaml";
 
 use super::{
 	label_match_set::LabelMatchSet,
+	label_scheme::LabelScheme,
 	parsed_label::LabelId,
 	rule::{Rule, Tag},
 	rule_filter::RuleFilter,
 };
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, fmt::Display, fs, path::PathBuf};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Display,
+	fs,
+	path::PathBuf,
+	sync::OnceLock,
+};
 
 /// [Specs] contains some meta information as well as the definitions of the [Label] set and
 /// the list of [Rule].
@@ -28,10 +35,22 @@ pub struct Specs {
 	pub name: String,
 	pub description: String,
 	pub version: Version,
-	pub labels: HashSet<Label>,
+
+	/// How the [LabelId] of the labels are extracted from their names.
+	/// It defaults to [LabelScheme::LetterNumber].
+	#[serde(default)]
+	label_scheme: LabelScheme,
+
+	/// The labels are only changed with [Specs::insert_label] so that [Specs::label_names]
+	/// stays in sync with them.
+	labels: HashSet<Label>,
 
 	// #[serde(flatten)]
 	pub rules: Vec<Rule>,
+
+	/// Derived from the [Specs::labels] and the [Specs::label_scheme], see [Specs::label_names]
+	#[serde(skip)]
+	label_names_cache: OnceLock<HashMap<LabelId, String>>,
 }
 
 // TODO: move that away from here
@@ -51,8 +70,8 @@ impl Display for Specs {
 
 		f.write_str("Rules:\n")?;
 
-		self.rules.iter().for_each(|rule| {
-			let _ = f.write_fmt(format_args!(" - {rule}\n"));
+		self.rules_by_priority().iter().for_each(|(_, rule)| {
+			let _ = f.write_fmt(format_args!(" - [{}] {rule}\n", rule.priority));
 		});
 
 		Ok(())
@@ -60,15 +79,108 @@ impl Display for Specs {
 }
 
 impl Specs {
+	/// Create [Specs] from their parts, [Specs::load] reads them from a yaml file instead.
+	pub fn new(
+		name: &str,
+		description: &str,
+		version: Version,
+		label_scheme: LabelScheme,
+		labels: HashSet<Label>,
+		rules: Vec<Rule>,
+	) -> Self {
+		Self {
+			name: name.to_string(),
+			description: description.to_string(),
+			version,
+			label_scheme,
+			labels,
+			rules,
+			label_names_cache: OnceLock::new(),
+		}
+	}
+
+	/// The [LabelScheme] identifying the labels.
+	pub fn label_scheme(&self) -> &LabelScheme {
+		&self.label_scheme
+	}
+
+	/// The definitions of the labels.
+	pub fn labels(&self) -> &HashSet<Label> {
+		&self.labels
+	}
+
+	/// Add a [Label] to the specs, returns false if it was already there.
+	pub fn insert_label(&mut self, label: Label) -> bool {
+		self.label_names_cache.take();
+		self.labels.insert(label)
+	}
+
+	/// Load [Specs] from a yaml file.
 	pub fn load(file_path: &PathBuf) -> Result<Self> {
 		let s = fs::read_to_string(PathBuf::from(file_path))?;
 		let res = serde_yaml::from_str::<Self>(&s)
 			.with_context(|| format!("Failed deserializing specs from {}", file_path.display()))?;
+		res.label_scheme.validate().map_err(anyhow::Error::msg)?;
+		res.rules.iter().try_for_each(|rule| {
+			rule.validate_message().map_err(|e| {
+				anyhow::anyhow!("Invalid message template in the rule `{}`: {e}", rule.name)
+			})
+		})?;
 
 		// Ok(res.attach_ref())
 		Ok(res)
 	}
 
+	/// Returns the [LabelId] of the label named `name` according to the [LabelScheme]
+	/// of the specs.
+	pub fn label_id(&self, name: &str) -> Result<LabelId, String> {
+		self.label_scheme.parse(name)
+	}
+
+	/// Returns the [LabelId] of each of the [Specs::labels] along with its name, the invalid
+	/// labels being left out. The labels are parsed on the first call after they changed.
+	pub fn label_names(&self) -> &HashMap<LabelId, String> {
+		self.label_names_cache.get_or_init(|| {
+			let mut names: Vec<&str> = self.labels.iter().map(|l| l.name.as_str()).collect();
+			names.sort();
+			let mut label_names = HashMap::new();
+			names.into_iter().for_each(|name| match self.label_id(name) {
+				Ok(id) => {
+					label_names.entry(id).or_insert_with(|| name.to_string());
+				},
+				Err(e) => log::warn!("Ignoring label {name}: {e}"),
+			});
+			label_names
+		})
+	}
+
+	/// Convert label names, for instance coming from a test case, into [LabelId]s.
+	/// It fails on the first label that is not valid according to the [LabelScheme].
+	pub fn label_ids<S: AsRef<str>>(&self, names: &[S]) -> Result<HashSet<LabelId>, String> {
+		names.iter().map(|name| self.label_id(name.as_ref())).collect()
+	}
+
+	/// Convert label names coming from Github into [LabelId]s. Unlike [Specs::label_ids], the
+	/// labels that are not valid according to the [LabelScheme] are set apart and returned
+	/// with the [LabelId]s of the others, the repository may have labels the specs do not cover.
+	pub fn partition_label_ids<S: AsRef<str>>(
+		&self,
+		names: &[S],
+	) -> (HashSet<LabelId>, Vec<String>) {
+		let mut ignored = Vec::new();
+		let ids = names
+			.iter()
+			.filter_map(|name| match self.label_id(name.as_ref()) {
+				Ok(id) => Some(id),
+				Err(_) => {
+					ignored.push(name.as_ref().to_string());
+					None
+				},
+			})
+			.collect();
+		(ids, ignored)
+	}
+
 	#[cfg(test)]
 	pub fn load_default() -> Result<Self> {
 		Self::load(&PathBuf::from(DEFAULT_SPEC_FILE))
@@ -79,28 +191,37 @@ impl Specs {
 		Self::load(&PathBuf::from(TEST_SPEC_FILE))
 	}
 
+	/// Returns the rules with their position in [Specs::rules], the highest priority first.
+	/// The rules of the same priority keep the order of the spec file.
+	pub fn rules_by_priority(&self) -> Vec<(usize, &Rule)> {
+		let mut rules: Vec<(usize, &Rule)> = self.rules.iter().enumerate().collect();
+		rules.sort_by_key(|(_, rule)| std::cmp::Reverse(rule.priority));
+		rules
+	}
+
 	/// This functions loops thru all rules and check the rule outcome.
+	/// The returned [CheckReport] contains one [RuleReport] per rule, in the order of
+	/// [Specs::rules_by_priority], including the rules that were filtered out.
+	/// Once a rule with `stop_on_failure` fails, the rules of lower priority are blocked.
 	pub fn run_checks(
 		&self,
 		labels: &HashSet<LabelId>,
 		run_skipped: bool,
-		color: bool,
-		verbose: bool,
 		tags: Option<Vec<Tag>>,
 		rule_filter: &Option<RuleFilter>,
-	) -> Vec<Option<bool>> {
+	) -> CheckReport {
 		log::debug!(
 			"     ‰ Running checks on {:?} labels: {}",
 			labels.len(),
 			labels.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", ")
 		);
 
-		let res: Vec<Option<bool>> = self
-			.rules
-			.iter()
-			.filter(|rule| !rule.disabled || run_skipped)
-			.filter(|rule| {
-				if let Some(filter) = rule_filter {
+		let mut blocked_below: Option<u32> = None;
+		let rules: Vec<RuleReport> = self
+			.rules_by_priority()
+			.into_iter()
+			.map(|(index, rule)| {
+				let filtered_in = if let Some(filter) = rule_filter {
 					if let Some(rule_id) = rule.id.as_ref() {
 						filter.id.contains(rule_id)
 					} else {
@@ -108,59 +229,50 @@ impl Specs {
 					}
 				} else {
 					true
-				}
-			})
-			.filter(|rule| {
-				match (&tags, &rule.tags) {
+				};
+
+				let tagged_in = match (&tags, &rule.tags) {
 					(None, None) | (None, Some(_)) => true, // no cli filter
 					(Some(_), None) => false,
 					(Some(o), Some(r)) =>
 						r.iter().filter(|rule_tag| o.contains(rule_tag)).count() >= o.len(),
-				}
-			})
-			.map(|rule| {
-				let check_result = rule.check(labels, self);
-				if verbose {
-					let title = format!("{} | {}", &rule.name, rule.spec.to_user_tip());
-					ResultPrinter::new(&title, TestResult::from(check_result))
-						.with_indent(8)
-						.with_color(color)
-						.print();
-				} else if let Some(output) = check_result {
-					if !output {
-						println!("{}", rule.spec.to_user_tip());
+				};
+
+				let outcome = if !filtered_in || !tagged_in || (rule.disabled && !run_skipped) {
+					RuleOutcome::Filtered
+				} else if rule.disabled {
+					RuleOutcome::Disabled
+				} else if blocked_below.is_some_and(|priority| rule.priority < priority) {
+					RuleOutcome::Blocked
+				} else {
+					match rule.check(labels, self) {
+						Some(true) => RuleOutcome::Passed,
+						Some(false) => RuleOutcome::Failed,
+						None => RuleOutcome::Skipped,
 					}
+				};
+
+				if outcome == RuleOutcome::Failed && rule.stop_on_failure {
+					log::debug!(
+						"{rule} failed, the rules below priority {} are blocked",
+						rule.priority
+					);
+					blocked_below.get_or_insert(rule.priority);
 				}
 
-				check_result
+				RuleReport::new(index, rule, outcome, labels, self)
 			})
 			.collect();
-		res
+
+		CheckReport::new(labels, rules)
 	}
 
-	/// The passed argument contains a Vec of test status. This functions returns
+	/// The passed [CheckReport] contains the outcome of each rule. This functions returns
 	/// references to the faulty rules. This is used to show up more information to the user.
-	pub fn find_faulty(&self, res: Vec<Option<bool>>) -> Vec<&Rule> {
-		log::trace!("results: {:?}", res);
-
-		let mut rule_iter = self.rules.iter();
-		let result: Vec<&Rule> = res
-			.iter()
-			.filter_map(|r| {
-				let rule = rule_iter.next().expect("We expect to have as many rules as results");
-				if let Some(rr) = r {
-					if !(*rr) {
-						Some(rule)
-					} else {
-						None
-					}
-				} else {
-					None
-				}
-			})
-			.collect();
+	pub fn find_faulty(&self, report: &CheckReport) -> Vec<&Rule> {
+		log::trace!("report: {:?}", report);
 
-		result
+		report.failed().filter_map(|r| self.rules.get(r.index)).collect()
 	}
 
 	/// In the yaml spec file, the user either explicitely lists some `LabelId` or provide
@@ -173,23 +285,16 @@ impl Specs {
 		set: &LabelMatchSet,
 		extra: Option<&HashSet<LabelId>>,
 	) -> HashSet<LabelId> {
-		let mut ref_set: HashSet<LabelId> = self
-			.labels
-			.iter()
-			.map(|label| LabelId::try_from(label.name.as_str()).unwrap())
-			.collect::<HashSet<_>>();
-		if let Some(ids) = extra {
-			let label_ids: HashSet<LabelId> = HashSet::from_iter(ids.clone());
-			ref_set.extend(label_ids.iter());
-		}
-		log::debug!("full ref_set : {:>3?} => {:?}", ref_set.len(), set_to_string(&ref_set));
-
-		// we now need to filter the full list according to the `set` and retain only the matches
-		ref_set
-			.iter()
+		// we filter the full list according to the `set` and retain only the matches
+		let ref_set: HashSet<LabelId> = self
+			.label_names()
+			.keys()
+			.chain(extra.into_iter().flatten())
 			.filter(|&label_id| set.matches_label(label_id).0)
-			.copied()
-			.collect()
+			.cloned()
+			.collect();
+		log::debug!("ref_set : {:>3?} => {:?}", ref_set.len(), set_to_string(&ref_set));
+		ref_set
 	}
 }
 
@@ -197,13 +302,14 @@ impl Specs {
 mod test_specs {
 	use super::*;
 	use crate::rllib::{
-		label_id_set::LabelIdSet, require::TokenRuleRequire, rule::*, rule_spec::RuleSpec,
+		condition::Condition, label_id_set::LabelIdSet, require::TokenRuleRequire, rule::*,
+		rule_spec::RuleSpec, severity::Severity,
 	};
 
 	#[test]
 	fn test_spec_serialize() {
 		let label_set = LabelMatchSet::from_str("B1");
-		let token_rule = TokenRuleRequire::One(label_set);
+		let token_rule = TokenRuleRequire(Condition::One(label_set));
 		let rs = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "Foo".to_string(),
@@ -212,17 +318,23 @@ mod test_specs {
 			disabled: false,
 			spec: rs,
 			tags: None,
+			severity: Severity::default(),
+			priority: DEFAULT_PRIORITY,
+			stop_on_failure: false,
+			message: None,
+			help_url: None,
 		};
 		// let rules = Rules { rules: vec![rule] };
 		let rules = vec![rule];
 
-		let specs: Specs = Specs {
-			name: "Foo".to_string(),
-			description: "desc".to_string(),
-			version: Version::new(0, 1, 0),
-			labels: HashSet::new(),
+		let specs: Specs = Specs::new(
+			"Foo",
+			"desc",
+			Version::new(0, 1, 0),
+			LabelScheme::default(),
+			HashSet::new(),
 			rules,
-		};
+		);
 
 		println!("{}", serde_yaml::to_string(&specs).unwrap());
 	}
@@ -239,7 +351,7 @@ mod test_specs {
 	#[test]
 	fn test_spec_ser_then_de() {
 		let label_set = LabelMatchSet::from_str("B1");
-		let token_rule = TokenRuleRequire::One(label_set);
+		let token_rule = TokenRuleRequire(Condition::One(label_set));
 		let rs = RuleSpec { require: Some(token_rule), exclude: None, when: None };
 		let rule = Rule {
 			name: "Foo".to_string(),
@@ -248,19 +360,25 @@ mod test_specs {
 			disabled: false,
 			spec: rs,
 			tags: None,
+			severity: Severity::default(),
+			priority: DEFAULT_PRIORITY,
+			stop_on_failure: false,
+			message: None,
+			help_url: None,
 		};
 		// let rules = Rules { rules: vec![rule] };
 		let rules = vec![rule];
 
-		let specs: Specs = Specs {
-			name: "Foo".to_string(),
-			description: "desc".to_string(),
-			version: Version::new(0, 1, 0),
-			labels: HashSet::new(),
+		let specs: Specs = Specs::new(
+			"Foo",
+			"desc",
+			Version::new(0, 1, 0),
+			LabelScheme::default(),
+			HashSet::new(),
 			rules,
-		};
+		);
 
-		let s = format!("{}", serde_yaml::to_string(&specs).unwrap());
+		let s = serde_yaml::to_string(&specs).unwrap();
 		println!("{}", s);
 		let new_specs: Specs = serde_yaml::from_str(&s).unwrap();
 
@@ -286,4 +404,214 @@ mod test_specs {
 
 		assert_eq!(LabelIdSet::from_str("A1,A2,B0,B1,B2, T9"), set);
 	}
+
+	#[test]
+	fn test_run_checks_report() {
+		let specs = Specs::load_test_default().unwrap();
+		let labels = LabelIdSet::from_str("B1,X1,X2,P2,J1");
+		let report = specs.run_checks(&labels, true, None, &None);
+
+		assert_eq!(specs.rules.len(), report.rules.len());
+		let b1_excludes_j =
+			report.rules.iter().find(|r| r.id == Some("b1_excludes_j".to_string())).unwrap();
+		assert_eq!(RuleOutcome::Failed, b1_excludes_j.outcome);
+		assert_eq!(Some(LabelIdSet::from_str("B1").into_iter().collect()), b1_excludes_j.when);
+		assert_eq!(Some(LabelIdSet::from_str("J1").into_iter().collect()), b1_excludes_j.exclude);
+		assert!(!report.passed);
+
+		let faulty = specs.find_faulty(&report);
+		assert!(faulty.iter().any(|rule| rule.id == Some("b1_excludes_j".to_string())));
+	}
+
+	#[test]
+	fn test_run_checks_report_filtered() {
+		let specs = Specs::load_test_default().unwrap();
+		let labels = LabelIdSet::from_str("B0");
+		let filter = RuleFilter { id: vec!["b_rules".to_string()] };
+		let report = specs.run_checks(&labels, true, None, &Some(filter));
+
+		assert!(report.passed);
+		report.rules.iter().for_each(|r| match r.id.as_deref() {
+			Some("b_rules") => assert_eq!(RuleOutcome::Passed, r.outcome),
+			_ => assert_eq!(RuleOutcome::Filtered, r.outcome),
+		});
+	}
+
+	#[test]
+	fn test_run_checks_severity() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_severity.yaml")).unwrap();
+
+		let report = specs.run_checks(&LabelIdSet::from_str("B1"), true, None, &None);
+		assert!(report.passed);
+		assert_eq!(Some(Severity::Warning), report.severity);
+		assert!(report.passes(Severity::Error));
+		assert!(!report.passes(Severity::Warning));
+		assert_eq!(0, report.exit_code(Severity::Error));
+		assert_eq!(2, report.exit_code(Severity::Info));
+		let report = report.with_fail_on(Severity::Warning);
+		assert!(!report.passed);
+		assert_eq!(Severity::Warning, report.fail_on);
+
+		let report = specs.run_checks(&LabelIdSet::from_str("B1, B2, D1"), true, None, &None);
+		assert!(!report.passed);
+		assert_eq!(Some(Severity::Error), report.severity);
+		assert_eq!(1, report.exit_code(Severity::Warning));
+
+		let report = specs.run_checks(&LabelIdSet::from_str("B2"), true, None, &None);
+		assert_eq!(None, report.severity);
+		assert_eq!(0, report.exit_code(Severity::Info));
+	}
+
+	#[test]
+	fn test_rules_by_priority() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_priority.yaml")).unwrap();
+		let ids: Vec<usize> = specs.rules_by_priority().iter().map(|(index, _)| *index).collect();
+		assert_eq!(vec![2, 1, 0], ids);
+
+		let report = specs.run_checks(&LabelIdSet::from_str("B1, X1"), true, None, &None);
+		let outcomes: Vec<(u32, RuleOutcome)> =
+			report.rules.iter().map(|r| (r.priority, r.outcome)).collect();
+		assert_eq!(
+			vec![
+				(1000, RuleOutcome::Passed),
+				(500, RuleOutcome::Failed),
+				(100, RuleOutcome::Passed)
+			],
+			outcomes
+		);
+	}
+
+	#[test]
+	fn test_run_checks_stop_on_failure() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_priority.yaml")).unwrap();
+		let report = specs.run_checks(&LabelIdSet::from_str("B1, B2"), true, None, &None);
+		let outcomes: Vec<RuleOutcome> = report.rules.iter().map(|r| r.outcome).collect();
+
+		assert_eq!(vec![RuleOutcome::Failed, RuleOutcome::Blocked, RuleOutcome::Blocked], outcomes);
+		assert_eq!(1, report.failed().count());
+	}
+
+	#[test]
+	fn test_run_checks_message() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_message.yaml")).unwrap();
+		let report = specs.run_checks(&LabelIdSet::from_str("B1, D1"), true, None, &None);
+		let texts: Vec<&str> = report.failed().map(|r| r.text()).collect();
+
+		assert_eq!(
+			vec!["B1 needs a priority, add one of P1, P2", "Remove D1 once the PR is ready"],
+			texts
+		);
+		assert_eq!(
+			Some("https://example.com/labels#priority"),
+			report.rules[0].help_url.as_deref()
+		);
+
+		let report = specs.run_checks(&LabelIdSet::from_str("B1, P1"), true, None, &None);
+		assert!(report.rules.iter().all(|r| r.message.is_none()));
+	}
+
+	#[test]
+	fn test_run_checks_message_missing() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_message.yaml")).unwrap();
+		specs.rules[0].message = Some("missing: {{ missing | join(sep=', ') }}".to_string());
+		let report = specs.run_checks(&LabelIdSet::from_str("B1"), true, None, &None);
+		assert_eq!(Some("missing: P1, P2"), report.rules[0].message.as_deref());
+
+		specs.rules[0].spec.require =
+			Some(TokenRuleRequire(Condition::None(LabelMatchSet::from_str("P*"))));
+		let report = specs.run_checks(&LabelIdSet::from_str("B1, P1"), true, None, &None);
+		assert_eq!(RuleOutcome::Failed, report.rules[0].outcome);
+		assert_eq!(Some("missing: "), report.rules[0].message.as_deref());
+	}
+
+	#[test]
+	fn test_load_invalid_message() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_message.yaml")).unwrap();
+		specs.rules[0].message = Some("{{ missing ".to_string());
+		assert!(specs.rules[0].validate_message().is_err());
+		assert!(specs.rules[1].validate_message().is_ok());
+	}
+
+	#[test]
+	fn test_label_ids_rejects_invalid_labels() {
+		let specs = Specs::load_test_default().unwrap();
+		let ids = specs.label_ids(&["B1-note_worthy", "X2"]);
+		assert_eq!(Ok(HashSet::from([LabelId::from("B1"), LabelId::from("X2")])), ids);
+
+		let err = specs.label_ids(&["B1-note_worthy", "bug", "X2"]).unwrap_err();
+		assert_eq!("Err 002: Invalid label, no regexp match: bug", err);
+	}
+
+	#[test]
+	fn test_insert_label() {
+		let mut specs = Specs::load_test_default().unwrap();
+		assert!(!specs.label_names().contains_key(&LabelId::from("Z1")));
+
+		let label =
+			Label { name: "Z1-new".to_string(), description: String::new(), color: String::new() };
+		assert!(specs.insert_label(label));
+		assert_eq!(Some(&"Z1-new".to_string()), specs.label_names().get(&LabelId::from("Z1")));
+	}
+
+	#[test]
+	fn test_partition_label_ids() {
+		let specs = Specs::load_test_default().unwrap();
+		let (ids, ignored) = specs.partition_label_ids(&["B1-note_worthy", "bug", "X2"]);
+
+		assert_eq!(HashSet::from([LabelId::from("B1"), LabelId::from("X2")]), ids);
+		assert_eq!(vec!["bug".to_string()], ignored);
+	}
+
+	#[test]
+	fn test_generate_label_set_multi_letter() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_categories.yaml")).unwrap();
+
+		let set = specs.generate_reference_set(&LabelMatchSet::from_str("TX*"), None);
+		assert_eq!(LabelIdSet::from_str("TX1,TX12"), set);
+
+		let extra = LabelIdSet::from_str("T3,TX2,TXY1");
+		let set = specs.generate_reference_set(&LabelMatchSet::from_str("T*"), Some(&extra));
+		assert_eq!(LabelIdSet::from_str("T1,T2,T3"), set);
+
+		let labels = LabelIdSet::from_str("T1,TX1,TX12");
+		assert!(specs.run_checks(&labels, true, None, &None).passed);
+		let labels = LabelIdSet::from_str("T2,T1");
+		assert!(!specs.run_checks(&labels, true, None, &None).passed);
+	}
+
+	#[test]
+	fn test_full_name_scheme() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_full_name.yaml")).unwrap();
+		let label_set = LabelMatchSet::from_str("bug,kind/*");
+		let set = specs.generate_reference_set(&label_set, None);
+
+		assert_eq!(
+			HashSet::from([
+				LabelId::new("", "bug"),
+				LabelId::new("kind/", "feature"),
+				LabelId::new("kind/", "refactor")
+			]),
+			set
+		);
+
+		let labels = specs.label_ids(&["kind/feature", "kind/refactor", "bug"]).unwrap();
+		assert!(!specs.run_checks(&labels, true, None, &None).passed);
+		let labels = specs.label_ids(&["kind/feature", "good first issue"]).unwrap();
+		assert!(specs.run_checks(&labels, true, None, &None).passed);
+	}
+
+	#[test]
+	fn test_invalid_label_scheme() {
+		let path =
+			std::env::temp_dir().join(format!("ruled-labels-scheme-{}.yaml", std::process::id()));
+		fs::write(
+			&path,
+			"name: x\ndescription: x\nversion: 0.1.0\nlabel_scheme: !regex '('\nlabels: []\nrules: []\n",
+		)
+		.unwrap();
+		let res = Specs::load(&path);
+		fs::remove_file(&path).unwrap();
+
+		assert!(format!("{:#}", res.unwrap_err()).contains("Invalid regex"));
+	}
 }
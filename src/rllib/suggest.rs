@@ -0,0 +1,311 @@
+//! Definitions of [Suggestion], [LabelChange] and [SuggestionPrinter].
+//!
+//! A [Suggestion] is the smallest set of labels to add and remove so that all the enabled
+//! rules of the [Specs] pass.
+
+use super::{parsed_label::LabelId, rule::Rule, specs::Specs};
+use serde::Serialize;
+use std::collections::HashSet;
+use termion::color;
+
+/// The number of changes the search stops at when no limit is passed to [Suggestion::new].
+/// The number of sets of labels to try grows with the power of the number of changes.
+pub const DEFAULT_MAX_CHANGES: usize = 5;
+
+/// Whether a label should be added or removed
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+	Add,
+	Remove,
+}
+
+/// A single label to add or remove, along with the rules needing it.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct LabelChange {
+	pub action: ChangeAction,
+	pub id: LabelId,
+
+	/// The name of the label in the specs, `None` for the labels the specs do not know
+	pub name: Option<String>,
+
+	/// The names of the rules failing without this change
+	pub rules: Vec<String>,
+}
+
+/// The result of [Suggestion::new].
+#[derive(Debug, Serialize, Clone)]
+pub struct Suggestion {
+	/// The labels that were checked, sorted
+	pub labels: Vec<LabelId>,
+
+	/// The changes making all the rules pass, empty if the labels already pass and `None`
+	/// if no valid set of labels was found
+	pub changes: Option<Vec<LabelChange>>,
+
+	/// True if all the sets of labels were tried. In that case, `changes` being `None`
+	/// means that no valid set of labels exists.
+	pub exhaustive: bool,
+}
+
+/// Returns the enabled rules of the `specs` failing with `labels`.
+fn failing_rules<'a>(specs: &'a Specs, labels: &HashSet<LabelId>) -> Vec<&'a Rule> {
+	specs
+		.rules
+		.iter()
+		.filter(|rule| !rule.disabled && rule.check(labels, specs) == Some(false))
+		.collect()
+}
+
+/// Returns `labels` with the `candidates` at the `toggles` positions added or removed.
+fn toggle(
+	labels: &HashSet<LabelId>,
+	candidates: &[LabelId],
+	toggles: &[usize],
+) -> HashSet<LabelId> {
+	let mut labels = labels.clone();
+	toggles.iter().for_each(|&i| {
+		if !labels.remove(&candidates[i]) {
+			labels.insert(candidates[i].clone());
+		}
+	});
+	labels
+}
+
+/// Try all the combinations of `k` positions out of `n`, in lexicographic order, until `f`
+/// returns true. Returns the successful combination.
+pub(crate) fn find_combination(
+	n: usize,
+	k: usize,
+	f: &mut dyn FnMut(&[usize]) -> bool,
+) -> Option<Vec<usize>> {
+	let mut combination: Vec<usize> = (0..k).collect();
+	if k > n {
+		return None
+	}
+	loop {
+		if f(&combination) {
+			return Some(combination)
+		}
+		// move to the next combination
+		let i = (0..k).rev().find(|&i| combination[i] < n - k + i)?;
+		combination[i] += 1;
+		(i + 1..k).for_each(|j| combination[j] = combination[j - 1] + 1);
+	}
+}
+
+impl Suggestion {
+	/// Search for the smallest set of labels to add or remove so that all the enabled rules
+	/// of the `specs` pass. The labels that may be added are those of the specs. Only the labels
+	/// matching at least one pattern of the rules are considered since the other ones do not
+	/// change the outcome. The search stops after `max_changes` changes, [DEFAULT_MAX_CHANGES]
+	/// by default.
+	pub fn new(specs: &Specs, labels: &HashSet<LabelId>, max_changes: Option<usize>) -> Self {
+		let sets: Vec<_> = specs
+			.rules
+			.iter()
+			.filter(|rule| !rule.disabled)
+			.flat_map(|rule| rule.spec.label_match_sets())
+			.collect();
+
+		let mut candidates: Vec<LabelId> = specs
+			.label_names()
+			.keys()
+			.chain(labels.iter().filter(|id| !specs.label_names().contains_key(id)))
+			.filter(|id| sets.iter().any(|set| set.matches_label(id).0))
+			.cloned()
+			.collect();
+		candidates.sort();
+		log::debug!("Suggestion candidates: {}", candidates.len());
+
+		let max = max_changes.unwrap_or(DEFAULT_MAX_CHANGES).min(candidates.len());
+		let toggles = (0..=max).find_map(|k| {
+			find_combination(candidates.len(), k, &mut |toggles| {
+				failing_rules(specs, &toggle(labels, &candidates, toggles)).is_empty()
+			})
+		});
+
+		let changes = toggles.map(|toggles| {
+			let fixed = toggle(labels, &candidates, &toggles);
+			toggles
+				.iter()
+				.map(|&i| {
+					let id = candidates[i].clone();
+					// Since the suggestion is minimal, undoing any of its changes breaks some rules
+					let mut undone = fixed.clone();
+					let action = if labels.contains(&id) {
+						undone.insert(id.clone());
+						ChangeAction::Remove
+					} else {
+						undone.remove(&id);
+						ChangeAction::Add
+					};
+					LabelChange {
+						action,
+						name: specs.label_names().get(&id).cloned(),
+						rules: failing_rules(specs, &undone)
+							.iter()
+							.map(|r| r.name.clone())
+							.collect(),
+						id,
+					}
+				})
+				.collect()
+		});
+
+		let mut sorted: Vec<LabelId> = labels.iter().cloned().collect();
+		sorted.sort();
+		Self { labels: sorted, changes, exhaustive: max == candidates.len() }
+	}
+
+	/// Returns the labels after applying the changes, sorted. `None` if no valid set was found.
+	pub fn fixed_labels(&self) -> Option<Vec<LabelId>> {
+		self.changes.as_ref().map(|changes| {
+			let mut labels: Vec<LabelId> = self
+				.labels
+				.iter()
+				.filter(|id| {
+					!changes.iter().any(|c| c.action == ChangeAction::Remove && &c.id == *id)
+				})
+				.chain(changes.iter().filter(|c| c.action == ChangeAction::Add).map(|c| &c.id))
+				.cloned()
+				.collect();
+			labels.sort();
+			labels
+		})
+	}
+}
+
+/// The [SuggestionPrinter] renders a [Suggestion] for humans.
+#[derive(Debug)]
+pub struct SuggestionPrinter<'a> {
+	suggestion: &'a Suggestion,
+	color: bool,
+}
+
+impl<'a> SuggestionPrinter<'a> {
+	pub fn new(suggestion: &'a Suggestion) -> Self {
+		Self { suggestion, color: true }
+	}
+
+	pub fn with_color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	fn paint(&self, s: &str, c: &dyn color::Color) -> String {
+		if self.color {
+			format!("{}{s}{}", color::Fg(c), color::Fg(color::Reset))
+		} else {
+			s.to_string()
+		}
+	}
+
+	pub fn print(&self) {
+		match &self.suggestion.changes {
+			None if self.suggestion.exhaustive =>
+				println!("No set of labels satisfies all the rules"),
+			None => println!("No set of labels satisfies all the rules within the allowed changes"),
+			Some(changes) if changes.is_empty() =>
+				println!("The labels already satisfy all the rules"),
+			Some(changes) => {
+				println!("To satisfy all the rules:");
+				changes.iter().for_each(|change| {
+					let label = match &change.name {
+						Some(name) if *name != change.id.to_string() =>
+							format!("{} ({name})", change.id),
+						_ => change.id.to_string(),
+					};
+					let sign = match change.action {
+						ChangeAction::Add => self.paint(&format!("+ {label}"), &color::Green),
+						ChangeAction::Remove => self.paint(&format!("- {label}"), &color::Red),
+					};
+					println!("  {sign} for {}", change.rules.join(", "));
+				});
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod test_suggest {
+	use super::*;
+	use crate::rllib::label_id_set::LabelIdSet;
+	use std::path::PathBuf;
+
+	fn suggest(labels: &str) -> Suggestion {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let labels = if labels.is_empty() { HashSet::new() } else { LabelIdSet::from_str(labels) };
+		Suggestion::new(&specs, &labels, None)
+	}
+
+	#[test]
+	fn test_find_combination() {
+		let mut seen = Vec::new();
+		assert_eq!(
+			None,
+			find_combination(4, 2, &mut |c| {
+				seen.push(c.to_vec());
+				false
+			})
+		);
+		assert_eq!(6, seen.len());
+		assert_eq!(vec![0, 1], seen[0]);
+		assert_eq!(vec![2, 3], seen[5]);
+		assert_eq!(Some(vec![]), find_combination(3, 0, &mut |_| true));
+		assert_eq!(None, find_combination(1, 2, &mut |_| true));
+	}
+
+	#[test]
+	fn test_nothing_to_change() {
+		let suggestion = suggest("B1, X1, X2, X3, P2");
+		assert_eq!(Some(vec![]), suggestion.changes);
+	}
+
+	#[test]
+	fn test_minimal_changes() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let labels = LabelIdSet::from_str("B1, X1, X2, X3, P1");
+		assert!(!specs.run_checks(&labels, true, None, &None).passed);
+
+		let suggestion = Suggestion::new(&specs, &labels, None);
+		let changes = suggestion.changes.as_ref().unwrap();
+		assert!(!changes.is_empty());
+		assert!(changes.iter().all(|c| !c.rules.is_empty()));
+
+		let fixed: HashSet<LabelId> = suggestion.fixed_labels().unwrap().into_iter().collect();
+		assert!(failing_rules(&specs, &fixed).is_empty());
+	}
+
+	#[test]
+	fn test_no_valid_set() {
+		let mut specs = Specs::load(&PathBuf::from("./tests/specs_mini.yaml")).unwrap();
+		let rule: Rule = serde_yaml::from_str(
+			"name: Impossible
+spec:
+  require: !one_of [ A1 ]
+  exclude: !some_of [ A* ]",
+		)
+		.unwrap();
+		specs.rules.push(rule);
+
+		let suggestion = Suggestion::new(&specs, &LabelIdSet::from_str("A2"), None);
+		assert_eq!(None, suggestion.changes);
+		assert!(suggestion.exhaustive);
+
+		let suggestion = Suggestion::new(&specs, &LabelIdSet::from_str("A2"), Some(1));
+		assert!(!suggestion.exhaustive);
+	}
+
+	#[test]
+	fn test_default_max_changes() {
+		let specs = Specs::load(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let suggestion = Suggestion::new(&specs, &HashSet::new(), None);
+		assert_eq!(5, suggestion.changes.unwrap().len());
+		assert!(!suggestion.exhaustive);
+
+		let suggestion = Suggestion::new(&specs, &HashSet::new(), Some(4));
+		assert_eq!(None, suggestion.changes);
+		assert!(!suggestion.exhaustive);
+	}
+}
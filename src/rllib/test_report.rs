@@ -0,0 +1,132 @@
+//! Definitions of [TestReport], [TestCaseReport], [TestStatus] and [TestReportPrinter].
+
+use super::{
+	check_report::{CheckReport, CheckReportPrinter},
+	coverage::Coverage,
+	test_result::{ResultPrinter, TestResult},
+};
+use serde::Serialize;
+
+/// The status of a single test case.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+	/// The outcome of the checks matched the expectation
+	Passed,
+
+	/// The outcome of the checks did not match the expectation
+	Failed,
+
+	/// The test did not run due to `skip`, `only` or the name filter
+	Skipped,
+}
+
+/// What happened to a single [TestSpec](super::tests::TestSpec).
+#[derive(Debug, Serialize, Clone)]
+pub struct TestCaseReport {
+	pub name: String,
+	pub description: Option<String>,
+	pub labels: Vec<String>,
+	pub expected: bool,
+
+	/// The aggregated result of the checks, `None` if the test was skipped
+	pub actual: Option<bool>,
+	pub status: TestStatus,
+
+	/// The detailed [CheckReport], `None` if the test was skipped
+	pub report: Option<CheckReport>,
+
+	/// Why the test failed without running the checks, such as an invalid label
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// The result of [Tests::run](super::tests::Tests::run): one [TestCaseReport] for each test
+/// case of the test file, including the skipped ones.
+#[derive(Debug, Serialize, Clone)]
+pub struct TestReport {
+	pub name: String,
+	pub specs_name: String,
+	pub specs_version: String,
+	pub passed: bool,
+	pub tests: Vec<TestCaseReport>,
+
+	/// The rules the tests exercised, only set with `--coverage`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub coverage: Option<Coverage>,
+}
+
+impl TestReport {
+	/// Returns the reports of the test cases that ran.
+	pub fn ran(&self) -> impl Iterator<Item = &TestCaseReport> {
+		self.tests.iter().filter(|t| t.status != TestStatus::Skipped)
+	}
+}
+
+impl From<TestStatus> for TestResult {
+	fn from(status: TestStatus) -> Self {
+		match status {
+			TestStatus::Passed => TestResult::Passed,
+			TestStatus::Failed => TestResult::Failed,
+			TestStatus::Skipped => TestResult::Skipped,
+		}
+	}
+}
+
+/// The [TestReportPrinter] renders a [TestReport] for humans.
+#[derive(Debug)]
+pub struct TestReportPrinter<'a> {
+	report: &'a TestReport,
+	color: bool,
+	dev: bool,
+}
+
+impl<'a> TestReportPrinter<'a> {
+	pub fn new(report: &'a TestReport) -> Self {
+		Self { report, color: true, dev: false }
+	}
+
+	pub fn with_color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	pub fn with_dev(mut self, dev: bool) -> Self {
+		self.dev = dev;
+		self
+	}
+
+	pub fn print(&self) {
+		self.report.ran().enumerate().for_each(|(index, test)| {
+			println!("\n    ▶️ Running test {:>2?}: {}", index + 1, test.name);
+			if self.dev {
+				println!(
+					"      Expected to {}",
+					match test.expected {
+						true => "PASS",
+						false => "FAIL",
+					}
+				);
+			}
+
+			if let Some(report) = &test.report {
+				CheckReportPrinter::new(report)
+					.with_color(self.color)
+					.with_verbose(self.dev)
+					.print();
+			}
+
+			let mut printer = ResultPrinter::new(&test.name, TestResult::from(test.status));
+			if let Some(error) = &test.error {
+				printer = printer.with_message_failed(error);
+			}
+			printer.with_indent(4).with_color(self.color).print();
+		});
+
+		ResultPrinter::new("OVERALL", TestResult::from(self.report.passed))
+			.with_message_passed("All expectations are OK")
+			.with_message_failed("Some expectations were not OK")
+			.with_color(self.color)
+			.print();
+	}
+}
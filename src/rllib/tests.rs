@@ -1,16 +1,15 @@
 //! Definitions of [Tests], [TestSpec] and [TestSpecs].
 
-use super::{rule_filter::RuleFilter, specs::Specs};
-use crate::rllib::{
-	parsed_label::LabelId,
-	test_result::{ResultPrinter, TestResult},
-};
+use super::{rule_filter::RuleFilter, severity::Severity, specs::Specs};
+use crate::rllib::test_report::{TestCaseReport, TestReport, TestStatus};
 use anyhow::{Context, Result};
 use regex::Regex;
-use serde::Deserialize;
-use std::{collections::HashSet, fs, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
 
-#[derive(Debug, Deserialize)]
+/// The content of a test file: a name, the [Specs] file to test against and a list of
+/// [TestSpec].
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Tests {
 	pub name: String,
 	pub spec_file: PathBuf,
@@ -19,23 +18,40 @@ pub struct Tests {
 	pub specs: TestSpecs,
 }
 
-#[derive(Debug, Deserialize)]
+/// Hold a vector of [TestSpec]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TestSpecs {
 	pub specs: Vec<TestSpec>,
 }
 
-#[derive(Debug, Deserialize)]
+/// A single test case: a set of labels and whether the [Specs] are expected to pass with them.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TestSpec {
 	pub name: String,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub description: Option<String>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub filter: Option<RuleFilter>,
+
 	pub labels: Vec<String>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub skip: Option<bool>,
-	pub filter: Option<RuleFilter>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub only: Option<bool>,
+
+	/// The lowest [Severity] of the failed rules failing the test case, `error` by default
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fail_on: Option<Severity>,
+
 	pub expected: bool,
 }
 
 impl Tests {
+	/// Load [Tests] from a yaml file.
 	pub fn load(file_path: &PathBuf) -> Result<Self> {
 		let s = fs::read_to_string(PathBuf::from(file_path))?;
 		serde_yaml::from_str::<Self>(&s)
@@ -45,109 +61,74 @@ impl Tests {
 	/// This is our test runner. It reads tests from a yaml file and apply the rules
 	/// from another (overridable) yaml file. The tests specification contain the expectations
 	/// for each test.
-	// unnecessary_fold if required in our case
-	#[allow(clippy::unnecessary_fold)]
-	pub fn run(
-		&self,
-		specs: Specs,
-		only: bool,
-		all: bool,
-		color: bool,
-		dev: bool,
-		filter: &Option<Regex>,
-	) {
-		let mut test_index = 0;
-
-		// TODO: use `only` and `all` filters to count the effective number of tests
-		let tests_count = self.specs.specs.len();
-
+	///
+	/// The returned [TestReport] contains all the test cases, including those that were
+	/// skipped due to `only`, `all` or the `filter`.
+	pub fn run(&self, specs: &Specs, only: bool, all: bool, filter: &Option<Regex>) -> TestReport {
 		log::info!("Running tests: {}", self.name);
-		log::info!("Found {:?} tests", tests_count);
+		log::info!("Found {:?} tests", self.specs.specs.len());
 		log::info!("Using specs: {}", specs.name);
 		log::info!("Using specs version: {}", specs.version.to_string());
 		log::debug!("Only: {:?}", only);
 		log::debug!("All : {:?}", all);
-		log::debug!("Color : {:?}", color);
 
 		// Iterate thru all the test specs
-		let overall_result = self
+		let tests: Vec<TestCaseReport> = self
 			.specs
 			.specs
 			.iter()
-			// if the --only flag was passeded, we consider only the `only` tests
-			.filter(|spec| {
-				if only {
-					if let Some(o) = spec.only {
-						o
-					} else {
-						false
-					}
-				} else {
-					true
-				}
-			})
-			// if --all was passed, we run all tests, including skipped ones
-			.filter(|spec| {
-				if all {
-					true
+			.map(|test_spec| {
+				// if the --only flag was passeded, we consider only the `only` tests
+				let selected = if only { test_spec.only.unwrap_or_default() } else { true };
+				// if --all was passed, we run all tests, including skipped ones
+				let skipped = !all && test_spec.skip.unwrap_or_default();
+				let matching =
+					if let Some(f) = filter { f.is_match(&test_spec.name) } else { true };
+
+				let (actual, status, report, error) = if !selected || skipped || !matching {
+					(None, TestStatus::Skipped, None, None)
 				} else {
-					match spec.skip {
-						None => true,
-						Some(skip) => !skip,
+					match specs.label_ids(&test_spec.labels) {
+						Ok(labels) => {
+							let report = specs
+								.run_checks(&labels, true, None, &test_spec.filter)
+								.with_fail_on(test_spec.fail_on.unwrap_or_default());
+							let aggregated_result = report.passed;
+							log::debug!("aggregated result for the test: {:?}", aggregated_result);
+							log::debug!("expected   result for the test: {:?}", test_spec.expected);
+
+							let status = if test_spec.expected == aggregated_result {
+								TestStatus::Passed
+							} else {
+								TestStatus::Failed
+							};
+							(Some(aggregated_result), status, Some(report), None)
+						},
+						// A typo in the labels must not go unnoticed
+						Err(e) => (None, TestStatus::Failed, None, Some(e)),
 					}
+				};
+
+				TestCaseReport {
+					name: test_spec.name.clone(),
+					description: test_spec.description.clone(),
+					labels: test_spec.labels.clone(),
+					expected: test_spec.expected,
+					actual,
+					status,
+					report,
+					error,
 				}
 			})
-			.filter(|spec| if let Some(f) = filter { f.is_match(&spec.name) } else { true })
-			.map(|test_spec| {
-				test_index += 1;
-				// TODO: Bring back the `test_count` once fixed and considers `all` and
-				// `only` println!(
-				// 	"\n    ▶️ Running test {:>2?}/{:<2?}: {}",
-				// 	test_index, tests_count, test_spec.name
-				// );
-				println!("\n    ▶️ Running test {:>2?}: {}", test_index, test_spec.name);
-				if dev {
-					println!(
-						"      Expected to {}",
-						match test_spec.expected {
-							true => "PASS",
-							false => "FAIL",
-						}
-					);
-				}
-				let labels: HashSet<LabelId> =
-					test_spec.labels.clone().iter().map(|s| LabelId::from(s.as_ref())).collect();
-
-				let results = specs.run_checks(&labels, true, color, dev, None, &test_spec.filter);
-
-				let aggregated_result = results.iter().fold(true, |acc, x| match x {
-					Some(v) => acc && *v,
-					None => acc,
-				});
-				log::debug!("aggregated result for the test: {:?}", aggregated_result);
-				log::debug!("expected   result for the test: {:?}", test_spec.expected);
-
-				ResultPrinter::new(
-					&test_spec.name,
-					TestResult::from(test_spec.expected == aggregated_result),
-				)
-				.with_indent(4)
-				.with_color(color)
-				.print();
-				test_spec.expected == aggregated_result
-			})
-			.fold(true, |acc, x| acc && x);
-
-		let result = TestResult::from(overall_result);
-		ResultPrinter::new("OVERALL", result.clone())
-			.with_message_passed("All expectations are OK")
-			.with_message_failed("Some expectations were not OK")
-			.with_color(color)
-			.print();
-
-		match result {
-			TestResult::Passed => std::process::exit(0),
-			_ => std::process::exit(1),
+			.collect();
+
+		TestReport {
+			name: self.name.clone(),
+			specs_name: specs.name.clone(),
+			specs_version: specs.version.to_string(),
+			passed: !tests.iter().any(|t| t.status == TestStatus::Failed),
+			tests,
+			coverage: None,
 		}
 	}
 }
@@ -0,0 +1,166 @@
+use super::count::LabelCount;
+use crate::rllib::{
+	common::set_to_string, label_match_set::LabelMatchSet, parsed_label::LabelId, specs::Specs,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
+
+/// A [Condition] on the labels, as found in `when`, `require` and `exclude`. The `all`, `any`
+/// and `not` conditions nest other conditions. For instance, "if B1 and not J*" is written:
+/// ```yaml
+/// when: !all
+///   - !one_of [ B1 ]
+///   - !not [ !some_of [ J* ] ]
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub enum Condition {
+	/// None from the set
+	#[serde(rename = "none_of")]
+	None(LabelMatchSet),
+
+	/// A single one from the set
+	#[serde(rename = "one_of")]
+	One(LabelMatchSet),
+
+	/// Any number from the set, at least one and
+	/// up to the full set
+	#[serde(rename = "some_of")]
+	Some(LabelMatchSet),
+
+	/// All of those in the set
+	#[serde(rename = "all_of")]
+	All(LabelMatchSet),
+
+	/// At least `n` from the set
+	#[serde(rename = "at_least")]
+	AtLeast(LabelCount),
+
+	/// At most `n` from the set, possibly none
+	#[serde(rename = "at_most")]
+	AtMost(LabelCount),
+
+	/// Exactly `n` from the set
+	#[serde(rename = "exactly")]
+	Exactly(LabelCount),
+
+	/// All the conditions hold
+	#[serde(rename = "all")]
+	And(Vec<Condition>),
+
+	/// At least one of the conditions holds
+	#[serde(rename = "any")]
+	Or(Vec<Condition>),
+
+	/// None of the conditions holds
+	#[serde(rename = "not")]
+	Not(Vec<Condition>),
+}
+
+impl Condition {
+	/// Returns true if the `labels` satisfy the condition.
+	pub fn holds(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		match self {
+			Condition::None(set) => set.matches_none(labels, specs),
+			Condition::One(set) => set.matches_one(labels, specs),
+			Condition::Some(set) => set.matches_some(labels, specs),
+			Condition::All(set) => set.matches_all(labels, specs),
+			Condition::AtLeast(count) => count.at_least(labels, specs),
+			Condition::AtMost(count) => count.at_most(labels, specs),
+			Condition::Exactly(count) => count.exactly(labels, specs),
+			Condition::And(conditions) => conditions.iter().all(|c| c.holds(labels, specs)),
+			Condition::Or(conditions) => conditions.iter().any(|c| c.holds(labels, specs)),
+			Condition::Not(conditions) => !conditions.iter().any(|c| c.holds(labels, specs)),
+		}
+	}
+
+	/// Returns all the [LabelMatchSet] of the tree.
+	pub fn label_match_sets(&self) -> Vec<&LabelMatchSet> {
+		match self {
+			Condition::None(set) |
+			Condition::One(set) |
+			Condition::Some(set) |
+			Condition::All(set) => vec![set],
+			Condition::AtLeast(count) | Condition::AtMost(count) | Condition::Exactly(count) =>
+				vec![&count.of],
+			Condition::And(conditions) | Condition::Or(conditions) | Condition::Not(conditions) =>
+				Self::sets(conditions),
+		}
+	}
+
+	/// Returns all the [LabelMatchSet] of the `conditions`.
+	pub fn sets(conditions: &[Condition]) -> Vec<&LabelMatchSet> {
+		conditions.iter().flat_map(|c| c.label_match_sets()).collect()
+	}
+
+	/// Describe the `conditions` joined with `separator`, the nested
+	/// `all` and `any` being wrapped in parenthesis.
+	pub fn join(conditions: &[Condition], separator: &str) -> String {
+		conditions
+			.iter()
+			.map(|c| match c {
+				Condition::And(_) | Condition::Or(_) => format!("({c})"),
+				_ => c.to_string(),
+			})
+			.collect::<Vec<String>>()
+			.join(separator)
+	}
+}
+
+impl Display for Condition {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Condition::None(set) =>
+				f.write_fmt(format_args!("none of the {} label(s)", set_to_string(set.iter()))),
+			Condition::One(set) =>
+				f.write_fmt(format_args!("one of the {} label(s)", set_to_string(set.iter()))),
+			Condition::Some(set) =>
+				f.write_fmt(format_args!("some of the {} label(s)", set_to_string(set.iter()))),
+			Condition::All(set) =>
+				f.write_fmt(format_args!("all of the {} label(s)", set_to_string(set.iter()))),
+			Condition::AtLeast(count) => f.write_fmt(format_args!("at least {count}")),
+			Condition::AtMost(count) => f.write_fmt(format_args!("at most {count}")),
+			Condition::Exactly(count) => f.write_fmt(format_args!("exactly {count}")),
+			Condition::And(conditions) =>
+				f.write_fmt(format_args!("{}", Self::join(conditions, " and "))),
+			Condition::Or(conditions) =>
+				f.write_fmt(format_args!("{}", Self::join(conditions, " or "))),
+			Condition::Not(conditions) =>
+				f.write_fmt(format_args!("not {}", Self::join(conditions, " nor "))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test_condition {
+	use super::*;
+	use crate::rllib::label_id_set::LabelIdSet;
+
+	fn condition(yaml: &str) -> Condition {
+		serde_yaml::from_str(yaml).unwrap()
+	}
+
+	#[test]
+	fn test_holds() {
+		let specs = &Specs::load_default().unwrap();
+		let c = condition("!all [ !one_of [ B1 ], !not [ !some_of [ J* ] ] ]");
+
+		assert!(c.holds(&LabelIdSet::from_str("B1, P1"), specs));
+		assert!(!c.holds(&LabelIdSet::from_str("B1, J1"), specs));
+		assert!(!c.holds(&LabelIdSet::from_str("B2"), specs));
+
+		let c = condition("!any [ !one_of [ B1 ], !at_least { n: 2, of: [ X* ] } ]");
+		assert!(c.holds(&LabelIdSet::from_str("X1, X2"), specs));
+		assert!(!c.holds(&LabelIdSet::from_str("X1"), specs));
+	}
+
+	#[test]
+	fn test_display_and_sets() {
+		let c = condition("!all [ !one_of [ B1 ], !not [ !some_of [ J* ] ], !any [ !one_of [ A1 ], !one_of [ A2 ] ] ]");
+
+		assert_eq!(
+			"one of the B1 label(s) and not some of the J* label(s) and (one of the A1 label(s) or one of the A2 label(s))",
+			c.to_string()
+		);
+		assert_eq!(4, c.label_match_sets().len());
+	}
+}
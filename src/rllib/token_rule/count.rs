@@ -0,0 +1,46 @@
+use crate::rllib::{
+	common::set_to_string, label_match_set::LabelMatchSet, parsed_label::LabelId, specs::Specs,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
+
+/// A number of labels from a set, used by the `at_least`, `at_most` and `exactly` tokens:
+/// ```yaml
+/// require: !at_most { n: 2, of: [ X* ] }
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct LabelCount {
+	/// The number of labels
+	pub n: usize,
+
+	/// The set the labels are counted from
+	pub of: LabelMatchSet,
+}
+
+impl LabelCount {
+	/// Create a new [LabelCount] of `n` labels from the `of` set.
+	pub fn new(n: usize, of: LabelMatchSet) -> Self {
+		Self { n, of }
+	}
+
+	/// Returns true if at least `n` of the `labels` match the set.
+	pub fn at_least(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		self.of.count_matches(labels, specs) >= self.n
+	}
+
+	/// Returns true if at most `n` of the `labels` match the set.
+	pub fn at_most(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		self.of.count_matches(labels, specs) <= self.n
+	}
+
+	/// Returns true if exactly `n` of the `labels` match the set.
+	pub fn exactly(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		self.of.count_matches(labels, specs) == self.n
+	}
+}
+
+impl Display for LabelCount {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_fmt(format_args!("{} of the {} label(s)", self.n, set_to_string(self.of.iter())))
+	}
+}
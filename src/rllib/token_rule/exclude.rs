@@ -1,21 +1,80 @@
-use crate::rllib::{common::set_to_string, label_match_set::LabelMatchSet};
+use super::{condition::Condition, count::LabelCount};
+use crate::rllib::{
+	common::set_to_string, label_match_set::LabelMatchSet, parsed_label::LabelId, specs::Specs,
+};
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{collections::HashSet, fmt::Display};
 
+/// The `exclude` token of a rule is the negation of the
+/// [TokenRuleRequire](super::require::TokenRuleRequire) token: `exclude: !some_of [J*]` fails when
+/// `require: !some_of [J*]` would pass, that is when its [Condition] holds.
+///
+/// The `exclude: !all_of` token is the exception: it excludes all the labels of its set and fails
+/// as soon as one of them is present, like `!some_of`. Failing only when all of them are present
+/// is written with the `!all` combinator: `exclude: !all [ !some_of [X1], !some_of [X2] ]`.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
-pub enum TokenRuleExclude {
-	/// All of those in the set
-	#[serde(rename = "all_of")]
-	All(LabelMatchSet),
+#[serde(transparent)]
+pub struct TokenRuleExclude(pub Condition);
+
+impl TokenRuleExclude {
+	/// Returns true if the `labels` pass the token, that is if they do not meet the condition.
+	pub fn passes(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		match &self.0 {
+			Condition::All(set) => set.matches_none(labels, specs),
+			condition => !condition.holds(labels, specs),
+		}
+	}
+
+	/// Returns the [LabelMatchSet]s this token applies to.
+	pub fn label_match_sets(&self) -> Vec<&LabelMatchSet> {
+		self.0.label_match_sets()
+	}
+
+	/// Returns true for an `!all_of` matching several of the `labels`. Such a token fails as
+	/// soon as one of them is present, which its name does not tell.
+	pub fn is_legacy_all_of<'a>(&self, labels: impl Iterator<Item = &'a LabelId>) -> bool {
+		match &self.0 {
+			Condition::All(set) => labels.filter(|id| set.matches_label(id).0).count() > 1,
+			_ => false,
+		}
+	}
+}
+
+fn of(count: &LabelCount) -> String {
+	format!("of the {} label(s)", set_to_string(count.of.iter()))
 }
 
 impl Display for TokenRuleExclude {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self {
-			TokenRuleExclude::All(set) => f.write_fmt(format_args!(
+		match &self.0 {
+			Condition::All(set) | Condition::Some(set) => f.write_fmt(format_args!(
 				"you need to exclude all of the {} label(s)",
 				set_to_string(set.iter())
 			)),
+			Condition::None(set) => f.write_fmt(format_args!(
+				"you cannot have none of the {} label(s)",
+				set_to_string(set.iter())
+			)),
+			Condition::One(set) => f.write_fmt(format_args!(
+				"you cannot have only one of the {} label(s)",
+				set_to_string(set.iter())
+			)),
+			Condition::AtLeast(count) =>
+				f.write_fmt(format_args!("you cannot have {} or more {}", count.n, of(count))),
+			Condition::AtMost(count) =>
+				f.write_fmt(format_args!("you cannot have {} or fewer {}", count.n, of(count))),
+			Condition::Exactly(count) =>
+				f.write_fmt(format_args!("you cannot have exactly {count}")),
+			Condition::And(conditions) => f.write_fmt(format_args!(
+				"you cannot have {}",
+				Condition::join(conditions, " and ")
+			)),
+			Condition::Or(conditions) =>
+				f.write_fmt(format_args!("you cannot have {}", Condition::join(conditions, " or "))),
+			Condition::Not(conditions) => f.write_fmt(format_args!(
+				"you need to include {}",
+				Condition::join(conditions, " or ")
+			)),
 		}
 	}
 }
@@ -1,6 +1,9 @@
 //! Definitions of [when::TokenRuleWhen], [require::TokenRuleRequire] and
-//! [exclude::TokenRuleExclude].
+//! [exclude::TokenRuleExclude] as well as the [condition::Condition] they wrap and the
+//! [count::LabelCount] it uses.
 
+pub mod condition;
+pub mod count;
 pub mod exclude;
 pub mod require;
 pub mod when;
@@ -1,47 +1,62 @@
-use std::fmt::Display;
-
-use crate::rllib::{common::set_to_string, label_match_set::LabelMatchSet};
+use super::condition::Condition;
+use crate::rllib::{
+	common::set_to_string, label_match_set::LabelMatchSet, parsed_label::LabelId, specs::Specs,
+};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
 
+/// The `require` token of a rule: the rule passes if its [Condition] holds.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
-pub enum TokenRuleRequire {
-	/// None from the set
-	#[serde(rename = "none_of")]
-	None(LabelMatchSet),
-
-	/// A single one from the set
-	#[serde(rename = "one_of")]
-	One(LabelMatchSet),
+#[serde(transparent)]
+pub struct TokenRuleRequire(pub Condition);
 
-	/// Any number from the set, at least one and
-	/// up to the full set
-	#[serde(rename = "some_of")]
-	Some(LabelMatchSet),
+impl TokenRuleRequire {
+	/// Returns true if the `labels` pass the token, that is if they meet the condition.
+	pub fn passes(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		self.0.holds(labels, specs)
+	}
 
-	/// All of those in the set
-	#[serde(rename = "all_of")]
-	All(LabelMatchSet),
+	/// Returns the [LabelMatchSet]s this token applies to.
+	pub fn label_match_sets(&self) -> Vec<&LabelMatchSet> {
+		self.0.label_match_sets()
+	}
 }
 
 impl Display for TokenRuleRequire {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self {
-			TokenRuleRequire::All(set) => f.write_fmt(format_args!(
+		match &self.0 {
+			Condition::All(set) => f.write_fmt(format_args!(
 				"you need to include all of the {} label(s)",
 				set_to_string(set.iter())
 			)),
-			TokenRuleRequire::None(set) => f.write_fmt(format_args!(
+			Condition::None(set) => f.write_fmt(format_args!(
 				"you need to include none of the {} label(s)",
 				set_to_string(set.iter())
 			)),
-			TokenRuleRequire::One(set) => f.write_fmt(format_args!(
+			Condition::One(set) => f.write_fmt(format_args!(
 				"you need to include one of the {} label(s)",
 				set_to_string(set.iter())
 			)),
-			TokenRuleRequire::Some(set) => f.write_fmt(format_args!(
+			Condition::Some(set) => f.write_fmt(format_args!(
 				"you need to include some of the {} label(s)",
 				set_to_string(set.iter())
 			)),
+			Condition::AtLeast(count) =>
+				f.write_fmt(format_args!("you need to include at least {count}")),
+			Condition::AtMost(count) =>
+				f.write_fmt(format_args!("you need to include at most {count}")),
+			Condition::Exactly(count) =>
+				f.write_fmt(format_args!("you need to include exactly {count}")),
+			Condition::And(conditions) => f.write_fmt(format_args!(
+				"you need to include {}",
+				Condition::join(conditions, " and ")
+			)),
+			Condition::Or(conditions) => f.write_fmt(format_args!(
+				"you need to include {}",
+				Condition::join(conditions, " or ")
+			)),
+			Condition::Not(conditions) =>
+				f.write_fmt(format_args!("you cannot have {}", Condition::join(conditions, " or "))),
 		}
 	}
 }
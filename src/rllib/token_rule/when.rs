@@ -1,47 +1,59 @@
-use std::fmt::Display;
-
-use crate::rllib::{common::set_to_string, label_match_set::LabelMatchSet};
+use super::condition::Condition;
+use crate::rllib::{
+	common::set_to_string, label_match_set::LabelMatchSet, parsed_label::LabelId, specs::Specs,
+};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
 
+/// The `when` token of a rule: the rule only applies when its [Condition] holds.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
-pub enum TokenRuleWhen {
-	/// None from the set
-	#[serde(rename = "none_of")]
-	None(LabelMatchSet),
-
-	/// A single one from the set
-	#[serde(rename = "one_of")]
-	One(LabelMatchSet),
+#[serde(transparent)]
+pub struct TokenRuleWhen(pub Condition);
 
-	/// Any number from the set, at least one and
-	/// up to the full set
-	#[serde(rename = "some_of")]
-	Some(LabelMatchSet),
+impl TokenRuleWhen {
+	/// Returns true if the `labels` meet the condition, the rule applies then.
+	pub fn holds(&self, labels: &HashSet<LabelId>, specs: &Specs) -> bool {
+		self.0.holds(labels, specs)
+	}
 
-	/// All of those in the set
-	#[serde(rename = "all_of")]
-	All(LabelMatchSet),
+	/// Returns the [LabelMatchSet]s this token applies to.
+	pub fn label_match_sets(&self) -> Vec<&LabelMatchSet> {
+		self.0.label_match_sets()
+	}
 }
 
 impl Display for TokenRuleWhen {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self {
-			TokenRuleWhen::All(set) => f.write_fmt(format_args!(
+		match &self.0 {
+			Condition::All(set) => f.write_fmt(format_args!(
 				"since you have all of the {} label(s)",
 				set_to_string(set.iter())
 			)),
-			TokenRuleWhen::None(set) => f.write_fmt(format_args!(
-				"since you none of {} label(s)",
+			Condition::None(set) => f.write_fmt(format_args!(
+				"since you have none of the {} label(s)",
 				set_to_string(set.iter())
 			)),
-			TokenRuleWhen::One(set) => f.write_fmt(format_args!(
+			Condition::One(set) => f.write_fmt(format_args!(
 				"since you have one of the {} label(s)",
 				set_to_string(set.iter())
 			)),
-			TokenRuleWhen::Some(set) => f.write_fmt(format_args!(
+			Condition::Some(set) => f.write_fmt(format_args!(
 				"since you have some of the {} label(s)",
 				set_to_string(set.iter())
 			)),
+			Condition::AtLeast(count) =>
+				f.write_fmt(format_args!("since you have at least {count}")),
+			Condition::AtMost(count) => f.write_fmt(format_args!("since you have at most {count}")),
+			Condition::Exactly(count) =>
+				f.write_fmt(format_args!("since you have exactly {count}")),
+			Condition::And(conditions) =>
+				f.write_fmt(format_args!("since you have {}", Condition::join(conditions, " and "))),
+			Condition::Or(conditions) =>
+				f.write_fmt(format_args!("since you have {}", Condition::join(conditions, " or "))),
+			Condition::Not(conditions) => f.write_fmt(format_args!(
+				"since you do not have {}",
+				Condition::join(conditions, " or ")
+			)),
 		}
 	}
 }
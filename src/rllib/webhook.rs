@@ -0,0 +1,566 @@
+//! A minimal webhook server checking the labels of pull requests as Github notifies us
+//! about them. The [WebhookHandler] does the actual work and does not know about HTTP,
+//! [serve] only parses the requests and writes the responses, each connection on its own thread.
+
+use super::{
+	github::{self, GithubApi, Repo, ReportTarget},
+	severity::Severity,
+	specs::Specs,
+};
+use anyhow::{bail, Context, Result};
+use ring::hmac;
+use serde::Deserialize;
+use std::{
+	fs,
+	io::{BufRead, BufReader, Read, Write},
+	net::{Shutdown, TcpListener, TcpStream},
+	panic::{self, AssertUnwindSafe},
+	path::PathBuf,
+	str::FromStr,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex, PoisonError,
+	},
+	thread,
+	time::{Duration, Instant, SystemTime},
+};
+
+/// The `pull_request` actions after which the labels are checked.
+pub const CHECKED_ACTIONS: [&str; 5] =
+	["opened", "reopened", "synchronize", "labeled", "unlabeled"];
+
+/// The `pull_request` payloads are a few dozen KB. The body is read before its signature
+/// can be verified so anyone can make us read that much.
+const MAX_PAYLOAD_SIZE: usize = 1024 * 1024;
+
+/// The default number of connections [serve] handles at once.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 32;
+
+/// The response to a webhook delivery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+	pub status: u16,
+	pub body: String,
+}
+
+impl Response {
+	fn new(status: u16, body: &str) -> Self {
+		Self { status, body: body.to_string() }
+	}
+}
+
+/// The subset of a `pull_request` event payload we need.
+#[derive(Debug, Deserialize)]
+pub struct PullRequestEvent {
+	pub action: String,
+	pub number: u64,
+	pub pull_request: PullRequest,
+	pub repository: Repository,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+	pub labels: Vec<EventLabel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventLabel {
+	pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Repository {
+	pub full_name: String,
+}
+
+/// Check the `X-Hub-Signature-256` header, formatted as `sha256=<hex digest>`,
+/// of a payload against our secret.
+pub fn verify_signature(secret: &[u8], payload: &[u8], signature: &str) -> bool {
+	let digest = match signature.strip_prefix("sha256=").and_then(decode_hex) {
+		Some(digest) => digest,
+		None => return false,
+	};
+	let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+	hmac::verify(&key, payload, &digest).is_ok()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+	if !s.len().is_multiple_of(2) {
+		return None
+	}
+	(0..s.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+		.collect()
+}
+
+/// Keeps the [Specs] loaded from a file and reloads them when the file changes on disk.
+pub struct SpecsWatcher {
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	specs: Arc<Specs>,
+}
+
+impl SpecsWatcher {
+	pub fn new(path: &PathBuf) -> Result<Self> {
+		let modified = Self::modified(path);
+		let specs = Arc::new(Specs::load(path)?);
+		Ok(Self { path: path.clone(), modified, specs })
+	}
+
+	fn modified(path: &PathBuf) -> Option<SystemTime> {
+		fs::metadata(path).and_then(|m| m.modified()).ok()
+	}
+
+	/// Returns the current [Specs], reloading them first if the file was modified.
+	/// If the new file cannot be loaded, we keep using the previous [Specs].
+	pub fn specs(&mut self) -> Arc<Specs> {
+		let modified = Self::modified(&self.path);
+		if modified != self.modified {
+			self.modified = modified;
+			match Specs::load(&self.path) {
+				Ok(specs) => {
+					log::info!("Reloaded the specs from {}", self.path.display());
+					self.specs = Arc::new(specs);
+				},
+				Err(e) => log::error!("Keeping the previous specs: {e:?}"),
+			}
+		}
+		Arc::clone(&self.specs)
+	}
+}
+
+/// Handles the webhook deliveries: it checks the labels of the pull requests and
+/// reports the result to Github.
+pub struct WebhookHandler<A: GithubApi> {
+	api: A,
+	secret: Vec<u8>,
+	target: ReportTarget,
+	fail_on: Severity,
+	specs: Mutex<SpecsWatcher>,
+}
+
+impl<A: GithubApi> WebhookHandler<A> {
+	/// The check of a pull request fails if a rule of severity `fail_on` or higher failed.
+	pub fn new(
+		api: A,
+		secret: &str,
+		target: ReportTarget,
+		fail_on: Severity,
+		specs: SpecsWatcher,
+	) -> Self {
+		let secret = secret.as_bytes().to_vec();
+		Self { api, secret, target, fail_on, specs: Mutex::new(specs) }
+	}
+
+	/// Handle one delivery given the values of its `X-GitHub-Event` and
+	/// `X-Hub-Signature-256` headers and its body.
+	pub fn handle(&self, event: Option<&str>, signature: Option<&str>, body: &[u8]) -> Response {
+		match signature {
+			Some(signature) if verify_signature(&self.secret, body, signature) => {},
+			_ => return Response::new(401, "Invalid signature"),
+		}
+
+		match event {
+			Some("ping") => Response::new(200, "pong"),
+			Some("pull_request") => match serde_json::from_slice::<PullRequestEvent>(body) {
+				Ok(event) => self.handle_pull_request(&event),
+				Err(e) => Response::new(400, &format!("Invalid payload: {e}")),
+			},
+			_ => Response::new(202, "Ignored event"),
+		}
+	}
+
+	fn handle_pull_request(&self, event: &PullRequestEvent) -> Response {
+		if !CHECKED_ACTIONS.contains(&event.action.as_str()) {
+			return Response::new(202, "Ignored action")
+		}
+		let repo = match Repo::from_str(&event.repository.full_name) {
+			Ok(repo) => repo,
+			Err(e) => return Response::new(400, &e),
+		};
+
+		let names: Vec<String> = event.pull_request.labels.iter().map(|l| l.name.clone()).collect();
+		let specs = self.specs.lock().unwrap_or_else(PoisonError::into_inner).specs();
+		let (labels, ignored) = specs.partition_label_ids(&names);
+		if !ignored.is_empty() {
+			log::warn!("{repo}#{}: ignoring the labels {}", event.number, ignored.join(", "));
+		}
+		let report = specs.run_checks(&labels, true, None, &None);
+		let passed = report.passes(self.fail_on);
+		log::info!("{repo}#{}: {}", event.number, if passed { "passed" } else { "failed" });
+
+		let target = self.target;
+		match github::report(&self.api, target, &repo, event.number, &report, &specs, self.fail_on)
+		{
+			Ok(()) => Response::new(200, if passed { "Passed" } else { "Failed" }),
+			Err(e) => {
+				log::error!("{e:?}");
+				Response::new(502, &format!("{e}"))
+			},
+		}
+	}
+}
+
+/// Read a request from the stream and returns the `X-GitHub-Event` and `X-Hub-Signature-256`
+/// headers along with the body.
+fn read_request(stream: &TcpStream) -> Result<(Option<String>, Option<String>, Vec<u8>)> {
+	let mut reader = BufReader::new(stream);
+
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line)?;
+	if !request_line.starts_with("POST ") {
+		bail!("Unsupported request: {}", request_line.trim())
+	}
+
+	let mut event = None;
+	let mut signature = None;
+	let mut content_length = 0;
+	loop {
+		let mut line = String::new();
+		reader.read_line(&mut line)?;
+		if line.trim().is_empty() {
+			break
+		}
+		if let Some((name, value)) = line.split_once(':') {
+			let value = value.trim().to_string();
+			match name.to_ascii_lowercase().as_str() {
+				"x-github-event" => event = Some(value),
+				"x-hub-signature-256" => signature = Some(value),
+				"content-length" =>
+					content_length = value.parse().context("Invalid Content-Length")?,
+				_ => {},
+			}
+		}
+	}
+	if content_length > MAX_PAYLOAD_SIZE {
+		bail!("Payload too large: {content_length} bytes")
+	}
+
+	let mut body = vec![0; content_length];
+	reader.read_exact(&mut body)?;
+	Ok((event, signature, body))
+}
+
+fn write_response(mut stream: &TcpStream, response: &Response) -> Result<()> {
+	let reason = match response.status {
+		200 => "OK",
+		202 => "Accepted",
+		400 => "Bad Request",
+		401 => "Unauthorized",
+		500 => "Internal Server Error",
+		502 => "Bad Gateway",
+		503 => "Service Unavailable",
+		_ => "",
+	};
+	write!(
+		stream,
+		"HTTP/1.1 {} {reason}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+		response.status,
+		response.body.len(),
+		response.body
+	)?;
+	Ok(())
+}
+
+/// Respond to the delivery sent on `stream`. A panic while handling it is answered
+/// with a `500` instead of dropping the connection.
+fn respond<A: GithubApi>(stream: TcpStream, handler: &WebhookHandler<A>) {
+	if let Err(e) = stream.set_read_timeout(Some(Duration::from_secs(10))) {
+		log::warn!("Failed setting the read timeout: {e}");
+		return
+	}
+
+	let response = match read_request(&stream) {
+		Ok((event, signature, body)) => panic::catch_unwind(AssertUnwindSafe(|| {
+			handler.handle(event.as_deref(), signature.as_deref(), &body)
+		}))
+		.unwrap_or_else(|_| Response::new(500, "Failed handling the delivery")),
+		Err(e) => Response::new(400, &format!("{e}")),
+	};
+	if let Err(e) = write_response(&stream, &response) {
+		log::warn!("Failed responding: {e}");
+	}
+}
+
+/// Answer a `503` on `stream`. The request is then read for a short while, closing the
+/// connection with unread data would reset it before the client reads the response.
+fn reject(mut stream: &TcpStream) {
+	if let Err(e) = write_response(stream, &Response::new(503, "Too many deliveries")) {
+		log::warn!("Failed responding: {e}");
+		return
+	}
+	let _ = stream.shutdown(Shutdown::Write);
+
+	let deadline = Instant::now() + Duration::from_millis(100);
+	let mut buffer = [0; 4096];
+	while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+		if stream.set_read_timeout(Some(left)).is_err() ||
+			!matches!(stream.read(&mut buffer), Ok(n) if n > 0)
+		{
+			break
+		}
+	}
+}
+
+/// One of the connections [serve] is handling, released when dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+	/// Take one of the `max` slots, `None` if they are all taken.
+	fn acquire(active: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+		active
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max).then_some(n + 1))
+			.ok()
+			.map(|_| Self(Arc::clone(active)))
+	}
+}
+
+impl Drop for ConnectionSlot {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+/// Serve the webhook deliveries received on `listener` until the process is stopped.
+/// Each connection is handled on its own thread so a slow client does not hold up the others.
+/// Up to `max_connections` are handled at once, the others are answered with a `503`.
+pub fn serve<A: GithubApi + Send + Sync + 'static>(
+	listener: TcpListener,
+	handler: Arc<WebhookHandler<A>>,
+	max_connections: usize,
+) -> Result<()> {
+	log::info!("Listening on {}", listener.local_addr()?);
+	let active = Arc::new(AtomicUsize::new(0));
+	for stream in listener.incoming() {
+		let stream = match stream {
+			Ok(stream) => stream,
+			Err(e) => {
+				log::warn!("Failed accepting a connection: {e}");
+				continue
+			},
+		};
+		let Some(slot) = ConnectionSlot::acquire(&active, max_connections) else {
+			log::warn!("Rejecting a connection, {max_connections} are already being handled");
+			reject(&stream);
+			continue
+		};
+		let handler = Arc::clone(&handler);
+		if let Err(e) = thread::Builder::new().spawn(move || {
+			respond(stream, &handler);
+			drop(slot);
+		}) {
+			log::error!("Failed spawning a thread for a connection: {e}");
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test_webhook {
+	use super::*;
+	use crate::rllib::github::IssueComment;
+	use anyhow::anyhow;
+	use std::net::SocketAddr;
+
+	const SECRET: &str = "It's a Secret to Everybody";
+
+	fn sign(body: &str) -> String {
+		let key = hmac::Key::new(hmac::HMAC_SHA256, SECRET.as_bytes());
+		let tag = hmac::sign(&key, body.as_bytes());
+		format!("sha256={}", tag.as_ref().iter().map(|b| format!("{b:02x}")).collect::<String>())
+	}
+
+	/// Records the statuses that would be set.
+	#[derive(Default)]
+	struct StatusRecorder(Mutex<Vec<bool>>);
+
+	impl GithubApi for StatusRecorder {
+		fn labels(&self, _repo: &Repo, _number: u64) -> Result<Vec<String>> {
+			Err(anyhow!("unexpected call"))
+		}
+
+		fn comments(&self, _repo: &Repo, _number: u64) -> Result<Vec<IssueComment>> {
+			Err(anyhow!("unexpected call"))
+		}
+
+		fn create_comment(&self, _repo: &Repo, _number: u64, _body: &str) -> Result<()> {
+			Err(anyhow!("unexpected call"))
+		}
+
+		fn update_comment(&self, _repo: &Repo, _comment_id: u64, _body: &str) -> Result<()> {
+			Err(anyhow!("unexpected call"))
+		}
+
+		fn head_sha(&self, _repo: &Repo, _number: u64) -> Result<String> {
+			Ok("abc123".to_string())
+		}
+
+		fn create_status(
+			&self,
+			_repo: &Repo,
+			_sha: &str,
+			passed: bool,
+			_description: &str,
+		) -> Result<()> {
+			self.0.lock().unwrap().push(passed);
+			Ok(())
+		}
+
+		fn add_labels(&self, _repo: &Repo, _number: u64, _names: &[String]) -> Result<()> {
+			Err(anyhow!("unexpected call"))
+		}
+
+		fn remove_label(&self, _repo: &Repo, _number: u64, _name: &str) -> Result<()> {
+			Err(anyhow!("unexpected call"))
+		}
+	}
+
+	fn handler() -> WebhookHandler<StatusRecorder> {
+		let specs = SpecsWatcher::new(&PathBuf::from("./tests/specs_ok.yaml")).unwrap();
+		let api = StatusRecorder::default();
+		WebhookHandler::new(api, SECRET, ReportTarget::GithubStatus, Severity::Error, specs)
+	}
+
+	fn payload(action: &str, labels: &[&str]) -> String {
+		let labels = labels
+			.iter()
+			.map(|l| format!(r#"{{"name":"{l}"}}"#))
+			.collect::<Vec<_>>()
+			.join(",");
+		format!(
+			r#"{{"action":"{action}","number":42,"pull_request":{{"labels":[{labels}]}},"repository":{{"full_name":"chevdor/ruled_labels"}}}}"#
+		)
+	}
+
+	#[test]
+	fn test_verify_signature() {
+		// The example from the Github documentation
+		assert!(verify_signature(
+			SECRET.as_bytes(),
+			b"Hello, World!",
+			"sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+		));
+		assert!(!verify_signature(
+			SECRET.as_bytes(),
+			b"Hello, World?",
+			"sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+		));
+		assert!(!verify_signature(SECRET.as_bytes(), b"Hello, World!", "sha256=zz"));
+		assert!(!verify_signature(SECRET.as_bytes(), b"Hello, World!", "sha1=757107"));
+	}
+
+	#[test]
+	fn test_handle_rejects_invalid_signatures() {
+		let handler = handler();
+		let body = payload("labeled", &["B1"]);
+
+		assert_eq!(401, handler.handle(Some("pull_request"), None, body.as_bytes()).status);
+		assert_eq!(
+			401,
+			handler.handle(Some("pull_request"), Some("sha256=00"), body.as_bytes()).status
+		);
+		assert!(handler.api.0.lock().unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_handle_pull_request() {
+		let handler = handler();
+
+		let body = payload("labeled", &["B1-note_worthy", "X1"]);
+		let response = handler.handle(Some("pull_request"), Some(&sign(&body)), body.as_bytes());
+		assert_eq!(Response::new(200, "Failed"), response);
+
+		let body = payload("unlabeled", &["B1-note_worthy", "X1", "X2", "X3", "P2"]);
+		let response = handler.handle(Some("pull_request"), Some(&sign(&body)), body.as_bytes());
+		assert_eq!(Response::new(200, "Passed"), response);
+
+		assert_eq!(vec![false, true], *handler.api.0.lock().unwrap());
+	}
+
+	#[test]
+	fn test_specs_watcher_reloads_modified_specs() {
+		let path =
+			std::env::temp_dir().join(format!("ruled-labels-watcher-{}.yaml", std::process::id()));
+		fs::copy("./tests/specs_ok.yaml", &path).unwrap();
+		let mut watcher = SpecsWatcher::new(&path).unwrap();
+		let name = watcher.specs().name.clone();
+
+		let specs = fs::read_to_string(&path).unwrap().replacen(&name, "Reloaded specs", 1);
+		fs::write(&path, specs).unwrap();
+		let file = fs::File::options().write(true).open(&path).unwrap();
+		file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+		assert_eq!("Reloaded specs", watcher.specs().name);
+
+		fs::write(&path, "not: [valid").unwrap();
+		file.set_modified(SystemTime::now() + Duration::from_secs(120)).unwrap();
+		assert_eq!("Reloaded specs", watcher.specs().name);
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn test_handle_ignores_other_events() {
+		let handler = handler();
+
+		let body = payload("closed", &["B1"]);
+		let response = handler.handle(Some("pull_request"), Some(&sign(&body)), body.as_bytes());
+		assert_eq!(202, response.status);
+
+		let body = "{}";
+		let response = handler.handle(Some("push"), Some(&sign(body)), body.as_bytes());
+		assert_eq!(202, response.status);
+		assert!(handler.api.0.lock().unwrap().is_empty());
+	}
+
+	fn send(addr: SocketAddr, request: &str) -> String {
+		let mut stream = TcpStream::connect(addr).unwrap();
+		stream.write_all(request.as_bytes()).unwrap();
+		let mut response = String::new();
+		stream.read_to_string(&mut response).unwrap();
+		response
+	}
+
+	#[test]
+	fn test_serve() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap();
+		thread::spawn(move || serve(listener, Arc::new(handler()), DEFAULT_MAX_CONNECTIONS));
+
+		// A client that never sends its body does not hold up the others
+		let mut slow = TcpStream::connect(addr).unwrap();
+		slow.write_all(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n").unwrap();
+
+		let body = payload("labeled", &["B1-note_worthy", "X1"]);
+		let response = send(
+			addr,
+			&format!(
+				"POST / HTTP/1.1\r\nX-GitHub-Event: pull_request\r\nX-Hub-Signature-256: {}\r\nContent-Length: {}\r\n\r\n{body}",
+				sign(&body),
+				body.len()
+			),
+		);
+		assert!(response.starts_with("HTTP/1.1 200 OK"));
+		assert!(response.ends_with("Failed"));
+
+		let response = send(
+			addr,
+			&format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", 2 * MAX_PAYLOAD_SIZE),
+		);
+		assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+		drop(slow);
+	}
+
+	#[test]
+	fn test_serve_rejects_beyond_max_connections() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap();
+		thread::spawn(move || serve(listener, Arc::new(handler()), 1));
+
+		let mut slow = TcpStream::connect(addr).unwrap();
+		slow.write_all(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n").unwrap();
+
+		let response = send(addr, "POST / HTTP/1.1\r\nContent-Length: 0\r\n\r\n");
+		assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+		drop(slow);
+	}
+}
@@ -29,6 +29,20 @@ mod cli_tests {
 			let assert = cmd.arg("lint").arg("./tests/specs_err.yaml").assert();
 			assert.failure().code(1);
 		}
+
+		#[test]
+		fn it_points_at_the_mistake() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd.arg("lint").arg("./tests/specs_typo.yaml").arg("--no-color").assert();
+			let output =
+				String::from_utf8(assert.failure().code(1).get_output().stdout.clone()).unwrap();
+
+			assert!(output
+				.contains("error: Unknown tag `!one_off` in rule `Exactly one visibility label`"));
+			assert!(output.contains("--> ./tests/specs_typo.yaml:13:16"));
+			assert!(output.contains("13 |       require: !one_off"));
+			assert!(output.contains("= help: did you mean `!one_of`?"));
+		}
 	}
 
 	#[cfg(test)]
@@ -63,6 +77,77 @@ mod cli_tests {
 			assert.success().code(0);
 		}
 
+		#[test]
+		fn it_accepts_large_label_numbers() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("check")
+				.arg("./tests/specs_ok.yaml")
+				.arg("-l")
+				.arg("B256-foo,X1")
+				.assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		fn it_calls_check_with_full_name_labels() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("check")
+				.arg("./tests/specs_full_name.yaml")
+				.arg("-l")
+				.arg("kind/feature,good first issue")
+				.assert();
+			assert.success().code(0);
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("check")
+				.arg("./tests/specs_full_name.yaml")
+				.arg("-l")
+				.arg("kind/feature,kind/refactor")
+				.assert();
+			assert.failure().code(1);
+		}
+
+		#[test]
+		fn it_calls_check_with_severities() {
+			let check = |labels: &str, fail_on: &str| {
+				Command::cargo_bin(env!("CARGO_PKG_NAME"))
+					.unwrap()
+					.arg("check")
+					.arg("./tests/specs_severity.yaml")
+					.arg("-l")
+					.arg(labels)
+					.arg("--fail-on")
+					.arg(fail_on)
+					.assert()
+			};
+
+			check("B2", "info").success().code(0);
+			let output = String::from_utf8(
+				check("B1", "error").success().code(0).get_output().stdout.clone(),
+			)
+			.unwrap();
+			assert!(output.contains("warning: Since you have one of the B1 label(s)"));
+			check("B1", "warning").failure().code(2);
+			check("B2,D1", "warning").success().code(0);
+			check("B2,D1", "info").failure().code(3);
+			check("B1,B2,D1", "info").failure().code(1);
+		}
+
+		#[test]
+		fn it_calls_check_with_messages() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert =
+				cmd.arg("check").arg("./tests/specs_message.yaml").arg("-l").arg("B1").assert();
+			let output =
+				String::from_utf8(assert.failure().code(1).get_output().stdout.clone()).unwrap();
+
+			assert!(output.contains("B1 needs a priority, add one of P1, P2\n"));
+			assert!(output.contains("  help: https://example.com/labels#priority\n"));
+		}
+
 		#[test]
 		fn it_calls_check_and_pass_with_comma_no_spaces() {
 			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
@@ -128,6 +213,103 @@ mod cli_tests {
 		}
 	}
 
+	#[cfg(test)]
+	mod analyze {
+		use assert_cmd::Command;
+
+		fn analyze(spec_file: &str) -> (i32, String) {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let output = cmd.arg("--no-color").arg("analyze").arg(spec_file).output().unwrap();
+			(output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+		}
+
+		#[test]
+		fn it_finds_nothing_in_sound_specs() {
+			let (code, output) = analyze("./tests/specs_priority.yaml");
+			assert_eq!(0, code);
+			assert_eq!("Analyzed 16 sets of the 4 labels\n", output);
+		}
+
+		#[test]
+		fn it_finds_conflicts() {
+			let (code, output) = analyze("./tests/specs_conflicts.yaml");
+			assert_eq!(1, code);
+			assert!(output.contains(
+				"error: The rule `Topics need all the priorities but the high one` never passes"
+			));
+			assert!(output.contains("warning: The `when` of the rule `Never checked` is never met"));
+			assert!(output.contains(
+				"error: The rules `Note worthy needs a priority` and `Note worthy has no priority` never pass together"
+			));
+		}
+	}
+
+	#[cfg(test)]
+	mod suggest {
+		use assert_cmd::Command;
+
+		fn suggest(args: &[&str]) -> (i32, String) {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let output = cmd
+				.arg("suggest")
+				.arg("./tests/specs_priority.yaml")
+				.arg("--no-color")
+				.args(args)
+				.output()
+				.unwrap();
+			(output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+		}
+
+		#[test]
+		fn it_suggests_labels_to_add() {
+			let (code, output) = suggest(&["-l", "B1"]);
+			assert_eq!(0, code);
+			assert!(output.contains("  + P1 (P1-high) for Note worthy needs a priority\n"));
+			assert!(output.contains("  + X1 (X1-topic) for Some topic\n"));
+		}
+
+		#[test]
+		fn it_suggests_labels_to_remove() {
+			let (code, output) = suggest(&["-l", "B1,B2,X1"]);
+			assert_eq!(0, code);
+			assert!(output.contains(
+				"  - B1 (B1-note_worthy) for Note worthy needs a priority, Exactly one B label\n"
+			));
+		}
+
+		#[test]
+		fn it_suggests_nothing_when_passing() {
+			let (code, output) = suggest(&["-l", "B2,X1"]);
+			assert_eq!(0, code);
+			assert!(output.contains("The labels already satisfy all the rules"));
+		}
+
+		#[test]
+		fn it_gives_up_after_max_changes() {
+			let (code, output) = suggest(&["--no-label", "--max-changes", "1"]);
+			assert_eq!(1, code);
+			assert!(output.contains("within the allowed changes"));
+		}
+
+		#[test]
+		fn it_calls_check_with_suggest() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("check")
+				.arg("./tests/specs_priority.yaml")
+				.arg("-l")
+				.arg("B2")
+				.arg("--suggest")
+				.arg("--no-color")
+				.assert();
+			let output =
+				String::from_utf8(assert.failure().code(1).get_output().stdout.clone()).unwrap();
+			assert!(
+				output.contains("To satisfy all the rules:\n  + X1 (X1-topic) for Some topic\n")
+			);
+		}
+	}
+
 	#[cfg(test)]
 	mod test {
 		use assert_cmd::Command;
@@ -170,11 +352,278 @@ mod cli_tests {
 			assert.failure().code(1);
 		}
 
+		#[test]
+		fn it_tests_with_fail_on() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd.arg("test").arg("./tests/tests_severity.yaml").assert();
+			assert.success().code(0);
+		}
+
+		#[test]
+		fn it_fails_on_an_invalid_label() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("--no-color")
+				.arg("test")
+				.arg("./tests/tests_invalid_label.yaml")
+				.assert();
+			let output = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+			assert.failure().code(1);
+			assert!(output.contains("FAILED  Err 002: Invalid label, no regexp match: bogus"));
+		}
+
+		#[test]
+		fn it_writes_a_junit_report() {
+			let junit = std::env::temp_dir().join("ruled_labels_junit.xml");
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("test")
+				.arg("./tests/tests_pass.yaml")
+				.arg("-s")
+				.arg("./tests/specs_ok.yaml")
+				.arg("--only")
+				.arg("--junit")
+				.arg(&junit)
+				.assert();
+			assert.success().code(0);
+
+			let xml = std::fs::read_to_string(&junit).unwrap();
+			assert!(xml.contains("<testsuite name=\"Name of the test\" tests=\"5\""));
+			assert_eq!(4, xml.matches("<skipped/>").count());
+		}
+
 		#[test]
 		fn it_passes_when_no_labels_required() {
 			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
 			let assert = cmd.arg("check").arg("./tests/specs_mini.yaml").arg("--no-label").assert();
 			assert.success().code(0);
 		}
+
+		fn coverage(args: &[&str]) -> (i32, String) {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let output = cmd
+				.arg("--no-color")
+				.arg("test")
+				.arg("./tests/tests_coverage.yaml")
+				.arg("--coverage")
+				.args(args)
+				.output()
+				.unwrap();
+			(output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+		}
+
+		#[test]
+		fn it_reports_coverage() {
+			let (code, output) = coverage(&[]);
+			assert_eq!(0, code);
+			assert!(output.contains("Coverage: 2 of 3 rules tested by 2 tests (66.7%)\n"));
+			assert!(output.contains("    PASSED  Some topic: 1 passed, 1 failed\n"));
+			assert!(output.contains(
+				"    FAILED  Note worthy needs a priority: 0 passed, 0 failed, 2 skipped, never tested\n"
+			));
+		}
+
+		#[test]
+		fn it_fails_below_min_coverage() {
+			assert_eq!(0, coverage(&["--min-coverage", "60"]).0);
+			assert_eq!(1, coverage(&["--min-coverage", "70"]).0);
+		}
+	}
+
+	#[cfg(test)]
+	mod gen_tests {
+		use assert_cmd::Command;
+
+		#[test]
+		fn it_generates_passing_tests() {
+			let output = std::env::temp_dir().join("ruled_labels_gen_tests.yaml");
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			cmd.arg("gen-tests").arg("./tests/specs_priority.yaml").arg("-o").arg(&output);
+			cmd.assert().success().code(0);
+
+			let yaml = std::fs::read_to_string(&output).unwrap();
+			assert!(yaml.contains("- name: one_b - require with 2 of B*\n"));
+
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("test")
+				.arg(&output)
+				.arg("-s")
+				.arg("./tests/specs_priority.yaml")
+				.arg("--coverage")
+				.arg("--min-coverage")
+				.arg("100")
+				.assert();
+			assert.success().code(0);
+		}
+
+		#[test]
+		fn it_warns_about_rules_without_id() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd.arg("gen-tests").arg("./tests/specs_mini.yaml").assert();
+			let output = assert.success().code(0).get_output().clone();
+			assert!(String::from_utf8(output.stdout).unwrap().contains("specs: []"));
+			assert!(String::from_utf8(output.stderr)
+				.unwrap()
+				.contains("The rule `Foo needs Bar` has no id, no tests were generated for it"));
+		}
+	}
+
+	#[cfg(test)]
+	mod doc {
+		use assert_cmd::Command;
+
+		#[test]
+		fn it_generates_markdown() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd.arg("doc").arg("./tests/specs_ok.yaml").assert();
+			let output =
+				String::from_utf8(assert.success().code(0).get_output().stdout.clone()).unwrap();
+
+			assert!(output.starts_with("# Label Documentation for chevdor/glabel"));
+			assert!(output.contains("- `B1-note_worthy`: note worthy"));
+		}
+
+		#[test]
+		fn it_generates_html() {
+			let html = std::env::temp_dir().join("ruled_labels_doc.html");
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("doc")
+				.arg("./tests/specs_ok.yaml")
+				.arg("--html")
+				.arg("-o")
+				.arg(&html)
+				.assert();
+			assert.success().code(0);
+
+			let html = std::fs::read_to_string(&html).unwrap();
+			assert!(html.contains("<h1>Label Documentation for chevdor/glabel</h1>"));
+		}
+
+		#[test]
+		fn it_fails_with_a_missing_template() {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let assert = cmd
+				.arg("doc")
+				.arg("./tests/specs_ok.yaml")
+				.arg("--template")
+				.arg("missing.tera")
+				.assert();
+			assert.failure();
+		}
+	}
+
+	#[cfg(test)]
+	mod json {
+		use assert_cmd::Command;
+		use serde_json::Value;
+
+		fn run_json(args: &[&str]) -> (Value, i32) {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			let output = cmd.arg("--format").arg("json").args(args).output().unwrap();
+			let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+			(value, output.status.code().unwrap())
+		}
+
+		#[test]
+		fn it_checks_as_json() {
+			let (value, code) = run_json(&["check", "./tests/specs_ok.yaml", "-l", "B1,X1"]);
+			assert_eq!(1, code);
+			assert_eq!(Value::Bool(false), value["passed"]);
+			assert_eq!("B1", value["labels"][0]);
+			assert!(value["rules"]
+				.as_array()
+				.unwrap()
+				.iter()
+				.any(|r| r["outcome"] == "failed" && r["tip"].is_string()));
+		}
+
+		#[test]
+		fn it_checks_as_json_with_fail_on() {
+			let args = ["check", "./tests/specs_severity.yaml", "-l", "B1", "--fail-on", "warning"];
+			let (value, code) = run_json(&args);
+			assert_eq!(2, code);
+			assert_eq!(Value::Bool(false), value["passed"]);
+			assert_eq!("warning", value["fail_on"]);
+		}
+
+		#[test]
+		fn it_tests_as_json() {
+			let (value, code) = run_json(&[
+				"test",
+				"./tests/tests_pass.yaml",
+				"-s",
+				"./tests/specs_ok.yaml",
+				"--only",
+			]);
+			assert_eq!(0, code);
+			assert_eq!(Value::Bool(true), value["passed"]);
+			let tests = value["tests"].as_array().unwrap();
+			assert!(tests.iter().any(|t| t["status"] == "skipped" && t["actual"].is_null()));
+			assert!(tests.iter().any(|t| t["status"] == "passed" && t["expected"] == t["actual"]));
+		}
+
+		#[test]
+		fn it_lists_as_json() {
+			let (value, code) = run_json(&["list", "./tests/specs_ok.yaml"]);
+			assert_eq!(0, code);
+			assert!(value["rules"].is_array());
+		}
+
+		#[test]
+		fn it_lints_as_json() {
+			let (value, code) = run_json(&["lint", "./tests/specs_err.yaml"]);
+			assert_eq!(1, code);
+			assert_eq!(Value::Bool(false), value["valid"]);
+			assert_eq!("error", value["diagnostics"][0]["level"]);
+		}
+
+		#[test]
+		fn it_suggests_as_json() {
+			let (value, code) = run_json(&["suggest", "./tests/specs_priority.yaml", "-l", "B1"]);
+			assert_eq!(0, code);
+			assert_eq!(Value::Bool(true), value["exhaustive"]);
+			let changes = value["changes"].as_array().unwrap();
+			assert_eq!("add", changes[0]["action"]);
+			assert_eq!("P1", changes[0]["id"]);
+			assert_eq!("P1-high", changes[0]["name"]);
+
+			let (value, _) = run_json(&["check", "./tests/specs_priority.yaml", "-l", "B2"]);
+			assert!(value.get("suggestion").is_none());
+			let (value, _) =
+				run_json(&["check", "./tests/specs_priority.yaml", "-l", "B2", "--suggest"]);
+			assert_eq!("X1", value["suggestion"]["changes"][0]["id"]);
+		}
+
+		#[test]
+		fn it_reports_coverage_as_json() {
+			let (value, code) = run_json(&[
+				"test",
+				"./tests/tests_coverage.yaml",
+				"--coverage",
+				"--min-coverage",
+				"100",
+			]);
+			assert_eq!(1, code);
+			assert_eq!(Value::Bool(true), value["passed"]);
+			assert_eq!(2, value["coverage"]["tests"]);
+			assert_eq!("b1_needs_p", value["coverage"]["rules"][1]["id"]);
+			assert_eq!(2, value["coverage"]["rules"][1]["skipped"]);
+
+			let (value, _) = run_json(&["test", "./tests/tests_coverage.yaml"]);
+			assert!(value.get("coverage").is_none());
+		}
+
+		#[test]
+		fn it_analyzes_as_json() {
+			let (value, code) = run_json(&["analyze", "./tests/specs_conflicts.yaml"]);
+			assert_eq!(1, code);
+			assert_eq!(32, value["sets"]);
+			let findings = value["findings"].as_array().unwrap();
+			assert_eq!(3, findings.len());
+			assert_eq!("conflict", findings[2]["kind"]);
+			assert_eq!("Note worthy has no priority", findings[2]["rules"][1]);
+		}
 	}
 }
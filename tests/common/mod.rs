@@ -0,0 +1,145 @@
+//! A minimal HTTP server standing in for the Github API in the tests.
+
+#![allow(dead_code)]
+
+use std::{
+	io::{BufRead, BufReader, Read, Write},
+	net::TcpListener,
+	sync::{Arc, Mutex},
+	thread,
+};
+
+/// A request received by the [MockServer]
+#[derive(Debug, Clone)]
+pub struct Request {
+	pub method: String,
+	pub path: String,
+	pub body: String,
+}
+
+/// A canned response: the first route whose method and path match is used. The query of
+/// the request is ignored unless the path of the route has one.
+#[derive(Debug, Clone)]
+pub struct Route {
+	pub method: &'static str,
+	pub path: String,
+	pub status: u16,
+	pub body: String,
+}
+
+impl Route {
+	pub fn new(method: &'static str, path: &str, status: u16, body: &str) -> Self {
+		Self { method, path: path.to_string(), status, body: body.to_string() }
+	}
+}
+
+pub struct MockServer {
+	pub url: String,
+	pub requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+	pub fn start(routes: Vec<Route>) -> Self {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+		let requests = Arc::new(Mutex::new(Vec::new()));
+		let recorded = requests.clone();
+
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let mut stream = stream.unwrap();
+				let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+				let mut request_line = String::new();
+				reader.read_line(&mut request_line).unwrap();
+				let mut parts = request_line.split_whitespace();
+				let method = parts.next().unwrap_or_default().to_string();
+				let path = parts.next().unwrap_or_default().to_string();
+
+				let mut content_length = 0;
+				loop {
+					let mut line = String::new();
+					reader.read_line(&mut line).unwrap();
+					if line.trim().is_empty() {
+						break
+					}
+					if let Some((name, value)) = line.split_once(':') {
+						if name.eq_ignore_ascii_case("content-length") {
+							content_length = value.trim().parse().unwrap();
+						}
+					}
+				}
+				let mut body = vec![0; content_length];
+				reader.read_exact(&mut body).unwrap();
+				let body = String::from_utf8(body).unwrap();
+
+				let route = routes.iter().find(|r| {
+					r.method == method &&
+						(r.path == path || r.path == path.split('?').next().unwrap_or_default())
+				});
+				let (status, response) = match route {
+					Some(r) => (r.status, r.body.clone()),
+					None => (404, r#"{"message":"Not Found"}"#.to_string()),
+				};
+				recorded.lock().unwrap().push(Request { method, path, body });
+
+				let _ = write!(
+					stream,
+					"HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+					response.len()
+				);
+			}
+		});
+
+		Self { url, requests }
+	}
+
+	pub fn requests(&self) -> Vec<Request> {
+		self.requests.lock().unwrap().clone()
+	}
+}
+
+/// A Github user as returned by the API
+pub fn user() -> String {
+	r#"{"login":"chevdor","id":1,"avatar_url":"","gravatar_id":"","url":"","html_url":"","followers_url":"","following_url":"","gists_url":"","starred_url":"","subscriptions_url":"","organizations_url":"","repos_url":"","events_url":"","received_events_url":"","site_admin":false}"#.to_string()
+}
+
+/// A Github label as returned by the API
+pub fn label(name: &str) -> String {
+	format!(r#"{{"url":"","name":"{name}","color":"ffffff","description":null}}"#)
+}
+
+/// A Github issue, with the passed labels, as returned by the API
+pub fn issue(number: u64, labels: &[&str]) -> String {
+	let labels = labels.iter().map(|l| label(l)).collect::<Vec<_>>().join(",");
+	format!(
+		r#"{{"id":{number},"url":"","labels_url":"","comments_url":"","events_url":"","html_url":"","number":{number},"state":"open","title":"Title","body":null,"user":{},"labels":[{labels}],"assignee":null,"locked":false,"comments":0,"pull_request":null,"closed_at":null,"created_at":"","updated_at":"","assignees":[]}}"#,
+		user()
+	)
+}
+
+/// A Github pull request, whose head is `sha`, as returned by the API
+pub fn pull(number: u64, sha: &str) -> String {
+	let commit = format!(r#"{{"label":"","ref":"branch","sha":"{sha}","user":{}}}"#, user());
+	format!(
+		r#"{{"id":{number},"url":"","html_url":"","diff_url":"","patch_url":"","issue_url":"","commits_url":"","review_comments_url":"","review_comment_url":"","comments_url":"","statuses_url":"","number":{number},"state":"open","title":"Title","body":null,"created_at":"","updated_at":"","closed_at":null,"merged_at":null,"head":{commit},"base":{commit},"user":{},"assignee":null,"assignees":[],"merge_commit_sha":null,"merged":false,"mergeable":null,"merged_by":null,"comments":null,"commits":null,"additions":null,"deletions":null,"changed_files":null,"labels":[]}}"#,
+		user()
+	)
+}
+
+/// A Github issue comment as returned by the API
+pub fn comment(id: u64, body: &str) -> String {
+	format!(
+		r#"{{"id":{id},"url":"","html_url":"","body":{},"user":{},"created_at":"","updated_at":""}}"#,
+		serde_json::to_string(body).unwrap(),
+		user()
+	)
+}
+
+/// A Github commit status as returned by the API
+pub fn status(state: &str) -> String {
+	format!(
+		r#"{{"created_at":null,"updated_at":null,"state":"{state}","target_url":null,"description":"","id":1,"url":"","context":"ruled-labels","creator":{}}}"#,
+		user()
+	)
+}
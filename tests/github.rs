@@ -0,0 +1,413 @@
+mod common;
+
+#[cfg(test)]
+mod github_tests {
+	use super::common::*;
+
+	#[cfg(test)]
+	mod check {
+		use super::*;
+		use assert_cmd::Command;
+
+		fn check(server: &MockServer, args: &[&str]) -> assert_cmd::assert::Assert {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			cmd.arg("check")
+				.arg("./tests/specs_ok.yaml")
+				.args(args)
+				.arg("--github-api")
+				.arg(&server.url)
+				.env("GITHUB_TOKEN", "secret")
+				.assert()
+		}
+
+		#[test]
+		fn it_checks_the_labels_of_a_pr_and_pass() {
+			let labels = ["B1-note_worthy", "X1", "X2", "X3", "P2-foo", "bug"];
+			let server = MockServer::start(vec![Route::new(
+				"GET",
+				"/repos/chevdor/ruled_labels/issues/42",
+				200,
+				&issue(42, &labels),
+			)]);
+
+			check(&server, &["--repo", "chevdor/ruled_labels", "--pr", "42"])
+				.success()
+				.code(0);
+			assert_eq!(1, server.requests().len());
+		}
+
+		#[test]
+		fn it_checks_the_labels_of_an_issue_and_fail() {
+			let server = MockServer::start(vec![Route::new(
+				"GET",
+				"/repos/chevdor/ruled_labels/issues/7",
+				200,
+				&issue(7, &["B1-note_worthy", "X1"]),
+			)]);
+
+			check(&server, &["--repo", "chevdor/ruled_labels", "--issue", "7"])
+				.failure()
+				.code(1);
+		}
+
+		#[test]
+		fn it_fails_when_the_pr_does_not_exist() {
+			let server = MockServer::start(vec![]);
+
+			check(&server, &["--repo", "chevdor/ruled_labels", "--pr", "1"]).failure();
+		}
+
+		#[test]
+		fn it_requires_a_repo() {
+			let server = MockServer::start(vec![]);
+
+			check(&server, &["--pr", "1"]).failure().code(2);
+		}
+
+		const FAILING: [&str; 2] = ["B1-note_worthy", "X1"];
+
+		#[test]
+		fn it_creates_a_comment_on_failure() {
+			let server = MockServer::start(vec![
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7", 200, &issue(7, &FAILING)),
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7/comments", 200, "[]"),
+				Route::new(
+					"POST",
+					"/repos/chevdor/ruled_labels/issues/7/comments",
+					201,
+					&comment(1, "created"),
+				),
+			]);
+
+			check(
+				&server,
+				&["--repo", "chevdor/ruled_labels", "--pr", "7", "--report", "github-comment"],
+			)
+			.failure()
+			.code(1);
+
+			let requests = server.requests();
+			let post = requests.iter().find(|r| r.method == "POST").expect("A comment was posted");
+			assert!(post.body.contains("<!-- ruled-labels -->"));
+			assert!(post.body.contains("Note Worthy need one Prio label"));
+		}
+
+		#[test]
+		fn it_updates_the_existing_comment() {
+			let comments =
+				format!("[{},{}]", comment(1, "LGTM"), comment(2, "<!-- ruled-labels -->\nold"));
+			let server = MockServer::start(vec![
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7", 200, &issue(7, &FAILING)),
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7/comments", 200, &comments),
+				Route::new(
+					"PATCH",
+					"/repos/chevdor/ruled_labels/issues/comments/2",
+					200,
+					&comment(2, "updated"),
+				),
+			]);
+
+			check(
+				&server,
+				&["--repo", "chevdor/ruled_labels", "--pr", "7", "--report", "github-comment"],
+			)
+			.failure()
+			.code(1);
+
+			let requests = server.requests();
+			assert!(requests.iter().all(|r| r.method != "POST"));
+			let patch =
+				requests.iter().find(|r| r.method == "PATCH").expect("The comment was updated");
+			assert!(patch.body.contains("Note Worthy need one Prio label"));
+		}
+
+		#[test]
+		fn it_finds_the_existing_comment_beyond_the_first_page() {
+			let first = (1..=100).map(|id| comment(id, "LGTM")).collect::<Vec<_>>().join(",");
+			let second = format!("[{}]", comment(101, "<!-- ruled-labels -->\nold"));
+			let server = MockServer::start(vec![
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7", 200, &issue(7, &FAILING)),
+				Route::new(
+					"GET",
+					"/repos/chevdor/ruled_labels/issues/7/comments?per_page=100&page=1",
+					200,
+					&format!("[{first}]"),
+				),
+				Route::new(
+					"GET",
+					"/repos/chevdor/ruled_labels/issues/7/comments?per_page=100&page=2",
+					200,
+					&second,
+				),
+				Route::new(
+					"PATCH",
+					"/repos/chevdor/ruled_labels/issues/comments/101",
+					200,
+					&comment(101, "updated"),
+				),
+			]);
+
+			check(
+				&server,
+				&["--repo", "chevdor/ruled_labels", "--pr", "7", "--report", "github-comment"],
+			)
+			.failure()
+			.code(1);
+
+			let requests = server.requests();
+			assert!(requests.iter().all(|r| r.method != "POST"));
+			assert!(requests.iter().any(|r| r.method == "PATCH"));
+		}
+
+		#[test]
+		fn it_does_not_comment_when_passing() {
+			let labels = ["B1-note_worthy", "X1", "X2", "X3", "P2-foo"];
+			let server = MockServer::start(vec![
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7", 200, &issue(7, &labels)),
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7/comments", 200, "[]"),
+			]);
+
+			check(
+				&server,
+				&["--repo", "chevdor/ruled_labels", "--pr", "7", "--report", "github-comment"],
+			)
+			.success();
+			assert!(server.requests().iter().all(|r| r.method == "GET"));
+		}
+
+		#[test]
+		fn it_sets_a_commit_status() {
+			let server = MockServer::start(vec![
+				Route::new("GET", "/repos/chevdor/ruled_labels/issues/7", 200, &issue(7, &FAILING)),
+				Route::new("GET", "/repos/chevdor/ruled_labels/pulls/7", 200, &pull(7, "abc123")),
+				Route::new(
+					"POST",
+					"/repos/chevdor/ruled_labels/statuses/abc123",
+					201,
+					&status("failure"),
+				),
+			]);
+
+			check(
+				&server,
+				&["--repo", "chevdor/ruled_labels", "--pr", "7", "--report", "github-status"],
+			)
+			.failure()
+			.code(1);
+
+			let requests = server.requests();
+			let post = requests.iter().find(|r| r.method == "POST").expect("A status was set");
+			assert!(post.body.contains(r#""state":"failure""#));
+			assert!(post.body.contains(r#""context":"ruled-labels""#));
+		}
+
+		#[test]
+		fn it_requires_a_pr_for_the_status() {
+			let server = MockServer::start(vec![Route::new(
+				"GET",
+				"/repos/chevdor/ruled_labels/issues/7",
+				200,
+				&issue(7, &FAILING),
+			)]);
+
+			check(
+				&server,
+				&["--repo", "chevdor/ruled_labels", "--issue", "7", "--report", "github-status"],
+			)
+			.failure();
+			assert!(server.requests().iter().all(|r| r.method == "GET"));
+		}
+	}
+
+	#[cfg(test)]
+	mod fix {
+		use super::*;
+		use assert_cmd::Command;
+
+		fn fix(server: &MockServer, args: &[&str]) -> assert_cmd::assert::Assert {
+			let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+			cmd.arg("check")
+				.arg("./tests/specs_priority.yaml")
+				.args(["--repo", "chevdor/ruled_labels", "--pr", "7", "--fix", "--no-color"])
+				.args(args)
+				.arg("--github-api")
+				.arg(&server.url)
+				.env("GITHUB_TOKEN", "secret")
+				.assert()
+		}
+
+		#[test]
+		fn it_removes_and_adds_labels() {
+			let server = MockServer::start(vec![
+				Route::new(
+					"GET",
+					"/repos/chevdor/ruled_labels/issues/7",
+					200,
+					&issue(7, &["B1-note_worthy", "B2-silent"]),
+				),
+				Route::new(
+					"DELETE",
+					"/repos/chevdor/ruled_labels/issues/7/labels/B1-note_worthy",
+					200,
+					"[]",
+				),
+				Route::new(
+					"POST",
+					"/repos/chevdor/ruled_labels/issues/7/labels",
+					200,
+					&format!("[{},{}]", label("B2-silent"), label("X1-topic")),
+				),
+			]);
+
+			fix(&server, &[]).success().code(0);
+
+			let requests = server.requests();
+			assert!(requests.iter().any(|r| r.method == "DELETE"));
+			let post = requests.iter().find(|r| r.method == "POST").expect("Labels were added");
+			assert_eq!(r#"["X1-topic"]"#, post.body);
+		}
+
+		#[test]
+		fn it_only_shows_the_changes_in_dry_run() {
+			let server = MockServer::start(vec![Route::new(
+				"GET",
+				"/repos/chevdor/ruled_labels/issues/7",
+				200,
+				&issue(7, &["B1-note_worthy", "B2-silent", "X1-topic"]),
+			)]);
+
+			let assert = fix(&server, &["--dry-run"]).failure().code(1);
+			let output = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+			assert!(
+				output.contains("Dry run: remove label `B1-note_worthy` on chevdor/ruled_labels#7")
+			);
+			assert!(server.requests().iter().all(|r| r.method == "GET"));
+		}
+
+		#[test]
+		fn it_refuses_to_remove_unknown_labels() {
+			let server = MockServer::start(vec![Route::new(
+				"GET",
+				"/repos/chevdor/ruled_labels/issues/7",
+				200,
+				&issue(7, &["B1-urgent", "B2-silent", "X1-topic"]),
+			)]);
+
+			fix(&server, &[]).failure();
+			assert!(server.requests().iter().all(|r| r.method == "GET"));
+		}
+	}
+
+	#[cfg(test)]
+	mod serve {
+		use super::*;
+		use ring::hmac;
+		use std::{
+			io::{Read, Write},
+			net::{TcpListener, TcpStream},
+			process::{Child, Command, Stdio},
+			thread,
+			time::Duration,
+		};
+
+		const SECRET: &str = "secret";
+
+		/// Kills the server when the test ends, even on failure.
+		struct Server(Child);
+
+		impl Drop for Server {
+			fn drop(&mut self) {
+				let _ = self.0.kill();
+			}
+		}
+
+		fn serve(github: &MockServer) -> (Server, String) {
+			let address =
+				TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+			let child = Command::new(assert_cmd::cargo::cargo_bin(env!("CARGO_PKG_NAME")))
+				.arg("serve")
+				.arg("./tests/specs_ok.yaml")
+				.arg("--listen")
+				.arg(&address)
+				.arg("--github-api")
+				.arg(&github.url)
+				.env("WEBHOOK_SECRET", SECRET)
+				.env("GITHUB_TOKEN", "token")
+				.stdout(Stdio::null())
+				.spawn()
+				.unwrap();
+
+			for _ in 0..100 {
+				if TcpStream::connect(&address).is_ok() {
+					break
+				}
+				thread::sleep(Duration::from_millis(50));
+			}
+			(Server(child), address)
+		}
+
+		fn post(address: &str, event: &str, body: &str, secret: &str) -> String {
+			let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+			let signature = hmac::sign(&key, body.as_bytes())
+				.as_ref()
+				.iter()
+				.map(|b| format!("{b:02x}"))
+				.collect::<String>();
+
+			let mut stream = TcpStream::connect(address).unwrap();
+			write!(
+				stream,
+				"POST / HTTP/1.1\r\nX-GitHub-Event: {event}\r\nX-Hub-Signature-256: sha256={signature}\r\nContent-Length: {}\r\n\r\n{body}",
+				body.len()
+			)
+			.unwrap();
+			let mut response = String::new();
+			stream.read_to_string(&mut response).unwrap();
+			response
+		}
+
+		fn payload(labels: &[&str]) -> String {
+			let labels = labels
+				.iter()
+				.map(|l| format!(r#"{{"name":"{l}"}}"#))
+				.collect::<Vec<_>>()
+				.join(",");
+			format!(
+				r#"{{"action":"labeled","number":7,"pull_request":{{"labels":[{labels}]}},"repository":{{"full_name":"chevdor/ruled_labels"}}}}"#
+			)
+		}
+
+		#[test]
+		fn it_sets_a_status_on_pull_request_events() {
+			let github = MockServer::start(vec![
+				Route::new("GET", "/repos/chevdor/ruled_labels/pulls/7", 200, &pull(7, "abc123")),
+				Route::new(
+					"POST",
+					"/repos/chevdor/ruled_labels/statuses/abc123",
+					201,
+					&status("failure"),
+				),
+			]);
+			let (_server, address) = serve(&github);
+
+			let response =
+				post(&address, "pull_request", &payload(&["B1-note_worthy", "X1"]), SECRET);
+			assert!(response.starts_with("HTTP/1.1 200"));
+			assert!(response.ends_with("Failed"));
+
+			let requests = github.requests();
+			let post = requests.iter().find(|r| r.method == "POST").expect("A status was set");
+			assert!(post.body.contains(r#""state":"failure""#));
+		}
+
+		#[test]
+		fn it_rejects_invalid_signatures() {
+			let github = MockServer::start(vec![]);
+			let (_server, address) = serve(&github);
+
+			let response = post(&address, "pull_request", &payload(&["B1"]), "wrong");
+			assert!(response.starts_with("HTTP/1.1 401"));
+			assert!(github.requests().is_empty());
+		}
+	}
+}